@@ -21,6 +21,8 @@ use fixed_type_id::{type_id, FixedId, FixedTypeId, FixedVersion};
 pub struct FixedTypeIdTagged<T: FixedTypeId> {
     /// The [`FixedId`] type id of the type.
     pub type_id: FixedId,
+    /// The [`FixedTypeId::TYPE_LAYOUT_HASH`] of the type.
+    pub layout_hash: u64,
     /// The data of the type.
     ///
     /// For [`rkyv`], this field is annotated with `#[rkyv(with = rkyv::with::AsBox)]`, because without it,
@@ -33,6 +35,7 @@ impl<T: FixedTypeId> From<T> for FixedTypeIdTagged<T> {
     fn from(data: T) -> Self {
         FixedTypeIdTagged {
             type_id: type_id::<T>(),
+            layout_hash: T::TYPE_LAYOUT_HASH,
             data,
         }
     }
@@ -40,8 +43,8 @@ impl<T: FixedTypeId> From<T> for FixedTypeIdTagged<T> {
 
 /// Used as the type deserialization target for data which deserialized by [`FixedTypeIdTagged`].
 ///
-/// When deserializing the data, first deserialize the data into a [`FixedTypeIdTag`], get the [`FixedId`]
-/// and [`FixedVersion`] metadata, then deserialize the data into the actual target type.
+/// When deserializing the data, first deserialize the data into a [`FixedTypeIdTag`], get the [`FixedId`],
+/// layout hash and [`FixedVersion`] metadata, then deserialize the data into the actual target type.
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 #[cfg_attr(
     feature = "rkyv",
@@ -52,6 +55,8 @@ impl<T: FixedTypeId> From<T> for FixedTypeIdTagged<T> {
 pub struct FixedTypeIdTag {
     /// The [`FixedId`] typeid of the deserialized type.
     pub type_id: FixedId,
+    /// The [`FixedTypeId::TYPE_LAYOUT_HASH`] of the deserialized type.
+    pub layout_hash: u64,
     /// The version of the deserialized data.
     ///
     /// for rkyv, the `with = Box<GeneralVersion>` must be specified, because the `data` will have different layout in [`FixedTypeIdTagged`].
@@ -297,6 +302,27 @@ impl fmt::Display for VersionTooNewError {
 
 impl core::error::Error for VersionTooNewError {}
 
+/// The layout hash of the deserialized tag doesn't match the layout of the type, with serde and rkyv.
+#[derive(Debug)]
+pub struct LayoutMismatchError {
+    /// The layout hash read from the tag.
+    pub deser_hash: u64,
+    /// The [`FixedTypeId::TYPE_LAYOUT_HASH`] of the type to deserialize.
+    pub expect_hash: u64,
+}
+
+impl fmt::Display for LayoutMismatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "layout hash mismatch, deser_hash:{:#x}, expect_hash:{:#x}",
+            self.deser_hash, self.expect_hash
+        )
+    }
+}
+
+impl core::error::Error for LayoutMismatchError {}
+
 /// Get [`FixedId`] and [`FixedVersion`] with serde.
 pub fn get_tag_serde<'de, F, T, E>(
     get_deserializer: F,
@@ -320,6 +346,9 @@ pub fn access_tag_rkyv(
 }
 
 pub mod perlude {
-    pub use super::{FixedTypeIdTag, FixedTypeIdTagged, TypeIdMismatchError, VersionTooNewError};
+    pub use super::{
+        FixedTypeIdTag, FixedTypeIdTagged, LayoutMismatchError, TypeIdMismatchError,
+        VersionTooNewError,
+    };
     pub use fixed_revision_macros::revisioned;
 }
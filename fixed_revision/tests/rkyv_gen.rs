@@ -4,7 +4,8 @@
 //! So currently `Clone` and `Copy` are not derived for archived type, you should implement them yourself.
 
 use fixed_revision::{
-    access_tag_rkyv, FixedTypeIdTag, FixedTypeIdTagged, TypeIdMismatchError, VersionTooNewError,
+    access_tag_rkyv, FixedTypeIdTag, FixedTypeIdTagged, LayoutMismatchError, TypeIdMismatchError,
+    VersionTooNewError,
 };
 use fixed_revision_macros::revisioned;
 use fixed_type_id::{type_id, type_name, type_version};
@@ -170,3 +171,18 @@ fn basic_gen() {
     let test_enum_deser = TestEnum::deserialize_rkyv(&test_enum_rkyv_aligned_vec).unwrap();
     assert_eq!(test_enum_deser, test_enum);
 }
+
+#[test]
+fn layout_mismatch() {
+    let test_enum = TestEnum::V3(TestEnum_3::Zero);
+    let tagged: FixedTypeIdTagged<TestEnum> = test_enum.clone().into();
+    assert_eq!(tagged.layout_hash, TestEnum::TYPE_LAYOUT_HASH);
+    let edited = FixedTypeIdTagged {
+        layout_hash: !TestEnum::TYPE_LAYOUT_HASH,
+        ..tagged
+    };
+    let bytes = to_bytes::<Error>(&edited).unwrap();
+    let err = TestEnum::deserialize_rkyv(&bytes).unwrap_err();
+    assert!(err.to_string().starts_with("layout hash mismatch"));
+    assert!(TestEnum::access_rkyv(&bytes).is_err());
+}
@@ -1,7 +1,8 @@
 use std::num::Wrapping;
 
 use fixed_revision::{
-    get_tag_serde, FixedTypeIdTag, FixedTypeIdTagged, TypeIdMismatchError, VersionTooNewError,
+    get_tag_serde, FixedTypeIdTag, FixedTypeIdTagged, LayoutMismatchError, TypeIdMismatchError,
+    VersionTooNewError,
 };
 use fixed_revision_macros::revisioned;
 use fixed_type_id::{type_id, type_name, type_version};
@@ -208,6 +209,7 @@ fn basic_gen() {
     let test_enum_str_edited = r#"
 (
     type_id: (9386386583157998584),
+    layout_hash: 9386386583157998584,
     data: (
         version: V4,
         content: Six,
@@ -222,6 +224,24 @@ fn basic_gen() {
         format!("{:?}", test_enum_edited_deser),
         "Err(Message(\"version too new, current_max:3, de_ver:4\"))"
     );
+    // a field changed without a version bump
+    let test_enum_str_edited = r#"
+(
+    type_id: (9386386583157998584),
+    layout_hash: 1,
+    data: (
+        version: V3,
+        content: Zero,
+    ),
+)
+"#;
+    let test_enum_edited_deser = TestEnum::deserialize_serde(|| {
+        ron::de::Deserializer::from_str(test_enum_str_edited).unwrap()
+    });
+    assert_eq!(
+        format!("{:?}", test_enum_edited_deser),
+        "Err(Message(\"layout hash mismatch, deser_hash:0x1, expect_hash:0x824324ddbda003f8\"))"
+    );
 
     let test_struct_v3_old = Tester4::V3(Tester4_3 {
         usize_1: 57918374,
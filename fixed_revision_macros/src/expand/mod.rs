@@ -259,6 +259,7 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
                     FixedTypeIdTagged {
                         data: val.into(),
                         type_id: type_id::<#enum_def_name>(),
+                        layout_hash: <#enum_def_name as FixedTypeId>::TYPE_LAYOUT_HASH,
                     }
                 }
             }
@@ -305,6 +306,12 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
                     if id != expect_id {
                         return Err(::serde::de::Error::custom(format!("type id mismatch, de:{}, expect:{}", id, expect_id)));
                     }
+                    if !<#enum_def_name as FixedTypeId>::layout_matches(tag.layout_hash) {
+                        return Err(::serde::de::Error::custom(self::LayoutMismatchError {
+                            deser_hash: tag.layout_hash,
+                            expect_hash: <#enum_def_name as FixedTypeId>::TYPE_LAYOUT_HASH,
+                        }));
+                    }
                     let current_max_ver = Self::max_type_version().major;
                     if current_max_ver < de_ver {
                         return Err(::serde::de::Error::custom(format!("version too new, current_max:{}, de_ver:{}", current_max_ver, de_ver)));
@@ -344,6 +351,13 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
                             expect_id
                         });
                     }
+                    let deser_hash = tag.layout_hash.to_native();
+                    if !<Self as FixedTypeId>::layout_matches(deser_hash) {
+                        ::rkyv::rancor::fail!(self::LayoutMismatchError {
+                            deser_hash,
+                            expect_hash: <Self as FixedTypeId>::TYPE_LAYOUT_HASH,
+                        });
+                    }
                     let current_max_ver = Self::max_type_version().major;
                     if current_max_ver < deser_ver {
                         ::rkyv::rancor::fail!(self::VersionTooNewError {
@@ -366,6 +380,13 @@ pub fn revision(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
                             expect_id
                         });
                     }
+                    let deser_hash = tag.layout_hash.to_native();
+                    if !<Self as FixedTypeId>::layout_matches(deser_hash) {
+                        ::rkyv::rancor::fail!(self::LayoutMismatchError {
+                            deser_hash,
+                            expect_hash: <Self as FixedTypeId>::TYPE_LAYOUT_HASH,
+                        });
+                    }
                     let current_max_ver = Self::max_type_version().major;
                     if current_max_ver < deser_ver {
                         ::rkyv::rancor::fail!(self::VersionTooNewError {
@@ -0,0 +1,36 @@
+//! With feature `rustc_version`, pass the version of the compiling rustc to the crate,
+//! as `FIXED_TYPE_ID_RUSTC_{MAJOR,MINOR,PATCH}`.
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+    if env::var_os("CARGO_FEATURE_RUSTC_VERSION").is_none() {
+        return;
+    }
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = Command::new(&rustc)
+        .arg("--version")
+        .output()
+        .unwrap_or_else(|error| panic!("failed to run `{rustc} --version`: {error}"));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // like `rustc 1.83.0 (90b35a623 2024-11-26)` or `rustc 1.85.0-nightly (...)`
+    let version = stdout
+        .split_whitespace()
+        .nth(1)
+        .and_then(|version| version.split(['-', '+']).next())
+        .unwrap_or_else(|| panic!("unexpected output of `{rustc} --version`: {stdout}"));
+    let parts: Vec<u64> = version
+        .split('.')
+        .map(|part| part.parse())
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|_| panic!("unexpected rustc version `{version}`"));
+    let [major, minor, patch] = parts[..] else {
+        panic!("unexpected rustc version `{version}`");
+    };
+    println!("cargo:rustc-env=FIXED_TYPE_ID_RUSTC_MAJOR={major}");
+    println!("cargo:rustc-env=FIXED_TYPE_ID_RUSTC_MINOR={minor}");
+    println!("cargo:rustc-env=FIXED_TYPE_ID_RUSTC_PATCH={patch}");
+}
@@ -0,0 +1,293 @@
+use alloc::boxed::Box;
+use core::any::Any;
+use core::mem::{align_of, align_of_val, size_of, size_of_val};
+
+use crate::{FixedId, FixedTypeId, FixedVersion};
+
+/// A build-stable replacement of [`core::any::Any`].
+///
+/// It's implemented for every `T: FixedTypeId + 'static`. [`FixedAny::fixed_id`] identifies the underlying type
+/// across different builds, like a hot-reloaded plugin and its host, as long as both sides agree on the [`FixedTypeId`] of the types.
+///
+/// Downcasting compares [`FixedTypeId::TYPE_ID`] instead of [`core::any::TypeId`], together with
+/// [`FixedTypeId::TYPE_NAME`], [`FixedTypeId::TYPE_VERSION`], [`FixedTypeId::TYPE_LAYOUT_HASH`], size and alignment.
+/// So types sharing an id, like types declared with `equal_to` or other versions with `omit_version_hash`, are never
+/// downcast into each other, use [`is_compatible`](Self::is_compatible) to accept them. It relies on the
+/// [`FixedTypeId`] implementations being unique per type on both sides, derive with `#[fixed_type_id(layout)]`
+/// to also catch fields changed without a version bump. When the underlying type is known to be `T` by other means,
+/// use the `*_unchecked` methods.
+///
+/// Note that `Box<dyn FixedAny>` may implement [`FixedAny`] itself, so call the methods on the trait object,
+/// `(*boxed).fixed_id()`, rather than on the box.
+///
+/// ## Example
+///
+/// ```rust
+/// use fixed_type_id::prelude::*;
+/// use fixed_type_id::FixedAny;
+///
+/// let mut value: Box<dyn FixedAny> = Box::new(42u32);
+/// assert!(value.is::<u32>());
+/// assert!(!value.is::<u64>());
+/// assert_eq!(value.downcast_ref::<u32>(), Some(&42));
+/// *value.downcast_mut::<u32>().unwrap() += 1;
+/// assert_eq!(*value.downcast::<u32>().unwrap(), 43);
+/// ```
+pub trait FixedAny: Any {
+    /// Returns the [`FixedTypeId::TYPE_ID`] of the underlying type.
+    fn fixed_id(&self) -> FixedId;
+
+    /// Returns the [`FixedTypeId::TYPE_NAME`] of the underlying type.
+    fn fixed_name(&self) -> &'static str;
+
+    /// Returns the [`FixedTypeId::TYPE_VERSION`] of the underlying type.
+    fn fixed_version(&self) -> FixedVersion;
+
+    /// Returns the [`FixedTypeId::TYPE_LAYOUT_HASH`] of the underlying type.
+    fn fixed_layout_hash(&self) -> u64;
+}
+
+impl<T: FixedTypeId + 'static> FixedAny for T {
+    #[inline(always)]
+    fn fixed_id(&self) -> FixedId {
+        T::TYPE_ID
+    }
+
+    #[inline(always)]
+    fn fixed_name(&self) -> &'static str {
+        T::TYPE_NAME
+    }
+
+    #[inline(always)]
+    fn fixed_version(&self) -> FixedVersion {
+        T::TYPE_VERSION
+    }
+
+    #[inline(always)]
+    fn fixed_layout_hash(&self) -> u64 {
+        T::TYPE_LAYOUT_HASH
+    }
+}
+
+/// Implement the downcasting methods and `Debug` for `dyn FixedAny` and its `Send`, `Sync` variants.
+macro_rules! impl_fixed_any_downcast {
+    ($($dyn_type:ty),*) => {
+        $(
+            impl $dyn_type {
+                /// Returns `true` if the underlying type is `T`, by its id, name, version, layout hash, size and alignment.
+                #[inline]
+                pub fn is<T: FixedTypeId + 'static>(&self) -> bool {
+                    self.fixed_id() == T::TYPE_ID
+                        && self.fixed_layout_hash() == T::TYPE_LAYOUT_HASH
+                        && self.fixed_version() == T::TYPE_VERSION
+                        && self.fixed_name() == T::TYPE_NAME
+                        && size_of_val(self) == size_of::<T>()
+                        && align_of_val(self) == align_of::<T>()
+                }
+
+                /// Returns `true` if the underlying type has the same [`FixedTypeId::TYPE_ID`] as `T`,
+                /// and its version is compatible with [`FixedTypeId::TYPE_VERSION`] of `T`, see [`FixedVersion::is_compatible`].
+                ///
+                /// It accepts types declared with `equal_to`, but it doesn't mean the value can be downcast to `T`, see [`Self::is`].
+                #[inline]
+                pub fn is_compatible<T: FixedTypeId + 'static>(&self) -> bool {
+                    self.fixed_id() == T::TYPE_ID && self.fixed_version().is_compatible(&T::TYPE_VERSION)
+                }
+
+                /// Returns a reference to the underlying value if it's of type `T`.
+                #[inline]
+                pub fn downcast_ref<T: FixedTypeId + 'static>(&self) -> Option<&T> {
+                    if self.is::<T>() {
+                        // SAFETY: the underlying type has the same `FixedTypeId` and layout as `T`.
+                        Some(unsafe { self.downcast_ref_unchecked() })
+                    } else {
+                        None
+                    }
+                }
+
+                /// Returns a mutable reference to the underlying value if it's of type `T`.
+                #[inline]
+                pub fn downcast_mut<T: FixedTypeId + 'static>(&mut self) -> Option<&mut T> {
+                    if self.is::<T>() {
+                        // SAFETY: the underlying type has the same `FixedTypeId` and layout as `T`.
+                        Some(unsafe { self.downcast_mut_unchecked() })
+                    } else {
+                        None
+                    }
+                }
+
+                /// Returns a reference to the underlying value as type `T`, without checking the type.
+                ///
+                /// # Safety
+                ///
+                /// The underlying value must be of type `T`, a matching [`FixedId`] is not enough.
+                /// [`Self::is`] checks it by the [`FixedTypeId`] of the types.
+                #[inline]
+                pub unsafe fn downcast_ref_unchecked<T: FixedTypeId + 'static>(&self) -> &T {
+                    debug_assert!(self.fixed_id() == T::TYPE_ID);
+                    unsafe { &*(self as *const Self as *const T) }
+                }
+
+                /// Returns a mutable reference to the underlying value as type `T`, without checking the type.
+                ///
+                /// # Safety
+                ///
+                /// The underlying value must be of type `T`, a matching [`FixedId`] is not enough.
+                /// [`Self::is`] checks it by the [`FixedTypeId`] of the types.
+                #[inline]
+                pub unsafe fn downcast_mut_unchecked<T: FixedTypeId + 'static>(&mut self) -> &mut T {
+                    debug_assert!((*self).fixed_id() == T::TYPE_ID);
+                    unsafe { &mut *(self as *mut Self as *mut T) }
+                }
+
+                /// Downcast the box to a concrete type, returns the box back if it's not of type `T`.
+                #[inline]
+                pub fn downcast<T: FixedTypeId + 'static>(self: Box<Self>) -> Result<Box<T>, Box<Self>> {
+                    if self.is::<T>() {
+                        // SAFETY: the underlying type has the same `FixedTypeId` and layout as `T`.
+                        Ok(unsafe { Box::from_raw(Box::into_raw(self) as *mut T) })
+                    } else {
+                        Err(self)
+                    }
+                }
+            }
+
+            impl core::fmt::Debug for $dyn_type {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    f.debug_struct("FixedAny")
+                        .field("type_name", &self.fixed_name())
+                        .finish_non_exhaustive()
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_any_downcast!(
+    dyn FixedAny,
+    dyn FixedAny + Send,
+    dyn FixedAny + Send + Sync
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::FixedAny;
+
+    mod versioned {
+        use crate::prelude::*;
+
+        #[derive(Debug)]
+        pub struct V1(pub u8);
+        #[derive(Debug)]
+        pub struct V2(pub u8);
+        #[derive(Debug)]
+        pub struct Other;
+
+        fixed_type_id! {
+            #[version((1,2,0))]
+            #[omit_version_hash]
+            tests::any::V1;
+        }
+        fixed_type_id! {
+            #[version((1,3,0))]
+            #[equal_to(V1)]
+            tests::any::V2;
+        }
+        fixed_type_id! {
+            #[version((2,0,0))]
+            #[equal_to(V1)]
+            tests::any::Other;
+        }
+    }
+    use versioned::*;
+
+    // the same type, as compiled into a host and a hot-reloaded plugin
+    mod host {
+        use crate::prelude::*;
+
+        #[derive(FixedTypeId)]
+        #[fixed_type_id(name = "tests::any::Plugin", layout)]
+        pub struct Plugin {
+            pub count: u32,
+        }
+    }
+
+    mod plugin {
+        use crate::prelude::*;
+
+        #[derive(FixedTypeId)]
+        #[fixed_type_id(name = "tests::any::Plugin", layout)]
+        pub struct Plugin {
+            pub count: u32,
+        }
+    }
+
+    mod changed {
+        use crate::prelude::*;
+
+        // a field added without a version bump
+        #[derive(Debug, FixedTypeId)]
+        #[fixed_type_id(name = "tests::any::Plugin", layout)]
+        pub struct Plugin {
+            pub count: u32,
+            pub total: u32,
+        }
+    }
+
+    #[test]
+    fn downcast() {
+        let mut value: Box<dyn FixedAny> = Box::new(String::from("fixed"));
+        assert_eq!((*value).fixed_id(), <String as FixedTypeId>::TYPE_ID);
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!((*value).fixed_name(), "alloc::string::String");
+        assert!(value.is::<String>());
+        assert!(!value.is::<&str>());
+        assert_eq!(value.downcast_ref::<String>().unwrap(), "fixed");
+        assert!(value.downcast_ref::<u8>().is_none());
+        value.downcast_mut::<String>().unwrap().push('!');
+        let value = value.downcast::<u8>().unwrap_err();
+        assert_eq!(*value.downcast::<String>().unwrap(), "fixed!");
+
+        let value: Box<dyn FixedAny + Send + Sync> = Box::new(1u8);
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(format!("{:?}", value), "FixedAny { type_name: \"u8\", .. }");
+        assert_eq!(*value.downcast::<u8>().unwrap(), 1);
+    }
+
+    #[test]
+    fn downcast_across_builds() {
+        let mut value: Box<dyn FixedAny> = Box::new(plugin::Plugin { count: 1 });
+        assert!(value.is::<host::Plugin>());
+        value.downcast_mut::<host::Plugin>().unwrap().count += 1;
+        assert!(!value.is::<changed::Plugin>());
+        let value = value.downcast::<changed::Plugin>().unwrap_err();
+        assert_eq!(value.downcast_ref::<plugin::Plugin>().unwrap().count, 2);
+        assert_eq!(value.downcast::<host::Plugin>().unwrap().count, 2);
+
+        let value: Box<dyn FixedAny> = Box::new(changed::Plugin { count: 1, total: 2 });
+        assert!(!value.is::<host::Plugin>());
+        let value = value.downcast::<changed::Plugin>().unwrap();
+        assert_eq!(value.count + value.total, 3);
+    }
+
+    #[test]
+    fn downcast_same_id() {
+        // a value produced by an older version of the type, with the same id
+        let mut old: Box<dyn FixedAny + Send> = Box::new(V1(1));
+        assert_eq!((*old).fixed_id(), <V2 as FixedTypeId>::TYPE_ID);
+        assert!(!old.is::<V2>());
+        assert!(old.downcast_ref::<V2>().is_none());
+        assert!(old.downcast_mut::<Other>().is_none());
+        assert_eq!(old.downcast_mut::<V1>().unwrap().0, 1);
+        // 1.2.0 doesn't satisfy ^1.3.0
+        assert!(!old.is_compatible::<V2>());
+        assert!(old.is_compatible::<V1>());
+
+        let new: Box<dyn FixedAny> = Box::new(V2(2));
+        assert!(new.is_compatible::<V1>());
+        assert!(!new.is_compatible::<Other>());
+        let new = new.downcast::<V1>().unwrap_err();
+        assert_eq!(new.downcast::<V2>().unwrap().0, 2);
+    }
+}
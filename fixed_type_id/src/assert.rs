@@ -0,0 +1,343 @@
+//! Assertions evaluated at compile time, to pin the ids and names of types in API crates.
+//!
+//! Each macro expands to a `const _: () = ...;` item, which evaluates [`FixedTypeId::TYPE_ID`](crate::FixedTypeId::TYPE_ID)
+//! or [`FixedTypeId::TYPE_NAME`](crate::FixedTypeId::TYPE_NAME), and fails to compile with a readable message
+//! when the assertion doesn't hold:
+//!
+//! ```rust
+//! # #![cfg_attr(feature = "specialization", feature(specialization))]
+//! use fixed_type_id::{assert_distinct_ids, assert_fixed_id, assert_same_id, assert_type_name};
+//! use fixed_type_id::{FixedId, FixedVersion};
+//!
+//! mod m {
+//!     use fixed_type_id::prelude::*;
+//!
+//!     pub struct A;
+//!     pub struct B;
+//!
+//!     fixed_type_id! {
+//!         #[version((0,1,0))]
+//!         m::A
+//!     }
+//!     fixed_type_id! {
+//!         #[equal_to(A)]
+//!         m::B
+//!     }
+//! }
+//! use m::*;
+//!
+//! # #[cfg(not(feature = "erase_name"))]
+//! assert_type_name!(A, "m::A");
+//! assert_fixed_id!(A, FixedId::from_type_name("m::A", Some(FixedVersion::new(0, 1, 0))).0);
+//! assert_same_id!(A, B);
+//! assert_distinct_ids!(A, u8, Vec<u8>);
+//! ```
+//!
+//! A failed assertion reports the type and both values:
+//!
+//! ```text
+//! error[E0080]: evaluation panicked: the id of `A` is 0x..., expected 0x0000000000001234
+//! ```
+
+use crate::RawFixedId;
+
+/// Assert the [`FixedId`](crate::FixedId) of a type at compile time, the id is a [`RawFixedId`](crate::RawFixedId),
+/// written as hex like the ids in manifests.
+///
+/// ```rust,ignore
+/// fixed_type_id::assert_fixed_id!(my_crate::MyType, 0x1f2e3d4c5b6a7988);
+/// ```
+#[macro_export]
+macro_rules! assert_fixed_id {
+    ($ty:ty, $id:expr $(,)?) => {
+        const _: () = $crate::assert::__private::fixed_id(
+            ::core::stringify!($ty),
+            <$ty as $crate::FixedTypeId>::TYPE_ID,
+            $id,
+        );
+    };
+}
+
+/// Assert the [`FixedTypeId::TYPE_NAME`](crate::FixedTypeId::TYPE_NAME) of a type at compile time.
+///
+/// ```rust,ignore
+/// fixed_type_id::assert_type_name!(my_crate::MyType, "my_crate::MyType");
+/// ```
+#[macro_export]
+macro_rules! assert_type_name {
+    ($ty:ty, $name:expr $(,)?) => {
+        const _: () = $crate::assert::__private::type_name(
+            ::core::stringify!($ty),
+            <$ty as $crate::FixedTypeId>::TYPE_NAME,
+            $name,
+        );
+    };
+}
+
+/// Assert that two types have the same [`FixedId`](crate::FixedId) at compile time, like types with `#[equal_to(...)]`.
+///
+/// ```rust,ignore
+/// fixed_type_id::assert_same_id!(my_crate::MyType, my_crate::MyAlias);
+/// ```
+#[macro_export]
+macro_rules! assert_same_id {
+    ($a:ty, $b:ty $(,)?) => {
+        const _: () = $crate::assert::__private::same_id(
+            [::core::stringify!($a), ::core::stringify!($b)],
+            [
+                <$a as $crate::FixedTypeId>::TYPE_ID,
+                <$b as $crate::FixedTypeId>::TYPE_ID,
+            ],
+        );
+    };
+}
+
+/// Assert that the given types have pairwise different [`FixedId`](crate::FixedId)s at compile time.
+///
+/// ```rust,ignore
+/// fixed_type_id::assert_distinct_ids!(my_crate::A, my_crate::B, my_crate::C);
+/// ```
+#[macro_export]
+macro_rules! assert_distinct_ids {
+    ($($ty:ty),+ $(,)?) => {
+        const _: () = $crate::assert::__private::distinct_ids(
+            &[$(::core::stringify!($ty)),+],
+            &[$(<$ty as $crate::FixedTypeId>::TYPE_ID),+],
+        );
+    };
+}
+
+/// The capacity of an assertion message, a longer message is truncated.
+const MESSAGE_CAPACITY: usize = 512;
+
+/// A message built in const context, to panic with.
+struct Message {
+    bytes: [u8; MESSAGE_CAPACITY],
+    len: usize,
+}
+
+impl Message {
+    const fn new() -> Self {
+        Message {
+            bytes: [0; MESSAGE_CAPACITY],
+            len: 0,
+        }
+    }
+
+    const fn push(mut self, s: &str) -> Self {
+        let s = s.as_bytes();
+        let mut i = 0;
+        while i < s.len() && self.len < MESSAGE_CAPACITY {
+            self.bytes[self.len] = s[i];
+            self.len += 1;
+            i += 1;
+        }
+        // don't cut a char when truncated
+        while i < s.len() && s[i] & 0xc0 == 0x80 {
+            i -= 1;
+            self.len -= 1;
+        }
+        self
+    }
+
+    /// Push the id as hex, with all the digits of [`RawFixedId`], as in manifests.
+    const fn push_id(self, id: RawFixedId) -> Self {
+        const DIGITS: usize = 2 * size_of::<RawFixedId>();
+        let mut hex = [0u8; 2 + DIGITS];
+        hex[0] = b'0';
+        hex[1] = b'x';
+        let mut i = 0;
+        while i < DIGITS {
+            let digit = ((id >> (4 * (DIGITS - 1 - i))) & 0xf) as u8;
+            hex[2 + i] = if digit < 10 {
+                b'0' + digit
+            } else {
+                b'a' + digit - 10
+            };
+            i += 1;
+        }
+        // SAFETY: the hex digits are ASCII.
+        self.push(unsafe { core::str::from_utf8_unchecked(&hex) })
+    }
+
+    const fn as_str(&self) -> &str {
+        match core::str::from_utf8(self.bytes.split_at(self.len).0) {
+            Ok(s) => s,
+            Err(_) => "the assertion message is not valid UTF-8",
+        }
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The functions called by the assertion macros.
+#[doc(hidden)]
+pub mod __private {
+    use super::{str_eq, Message};
+    use crate::{FixedId, RawFixedId};
+
+    pub const fn fixed_id(ty: &str, actual: FixedId, expected: RawFixedId) {
+        if actual.0 != expected {
+            let message = Message::new()
+                .push("the id of `")
+                .push(ty)
+                .push("` is ")
+                .push_id(actual.0)
+                .push(", expected ")
+                .push_id(expected);
+            panic!("{}", message.as_str());
+        }
+    }
+
+    pub const fn type_name(ty: &str, actual: &str, expected: &str) {
+        if !str_eq(actual, expected) {
+            let message = Message::new()
+                .push("the type name of `")
+                .push(ty)
+                .push("` is \"")
+                .push(actual)
+                .push("\", expected \"")
+                .push(expected)
+                .push("\"");
+            panic!("{}", message.as_str());
+        }
+    }
+
+    pub const fn same_id(types: [&str; 2], ids: [FixedId; 2]) {
+        if ids[0].0 != ids[1].0 {
+            let message = Message::new()
+                .push("`")
+                .push(types[0])
+                .push("` and `")
+                .push(types[1])
+                .push("` have different ids, ")
+                .push_id(ids[0].0)
+                .push(" and ")
+                .push_id(ids[1].0);
+            panic!("{}", message.as_str());
+        }
+    }
+
+    pub const fn distinct_ids(types: &[&str], ids: &[FixedId]) {
+        let mut i = 0;
+        while i < ids.len() {
+            let mut j = i + 1;
+            while j < ids.len() {
+                if ids[i].0 == ids[j].0 {
+                    let message = Message::new()
+                        .push("`")
+                        .push(types[i])
+                        .push("` and `")
+                        .push(types[j])
+                        .push("` have the same id ")
+                        .push_id(ids[i].0);
+                    panic!("{}", message.as_str());
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert::{__private, Message};
+    use crate::prelude::*;
+
+    mod m {
+        use crate::prelude::*;
+
+        pub struct A;
+        pub struct B;
+
+        fixed_type_id! {
+            #[version((0,1,0))]
+            m::A
+        }
+        fixed_type_id! {
+            #[equal_to(A)]
+            m::B
+        }
+    }
+    use m::*;
+
+    #[cfg(not(feature = "erase_name"))]
+    assert_type_name!(A, "m::A");
+    #[cfg(not(feature = "erase_name"))]
+    assert_type_name!(Vec<u8>, "alloc::vec::Vec<u8>");
+    assert_fixed_id!(
+        A,
+        FixedId::from_type_name("m::A", Some(FixedVersion::new(0, 1, 0))).0
+    );
+    assert_same_id!(A, B);
+    assert_distinct_ids!(A, u8, Vec<u8>, (u8, u16));
+
+    #[test]
+    fn messages() {
+        assert_eq!(
+            Message::new().push_id(0x1f).as_str(),
+            format!(
+                "{:#0width$x}",
+                0x1f,
+                width = 2 + 2 * size_of::<crate::RawFixedId>()
+            )
+        );
+        let truncated = Message::new().push("a").push(&"é".repeat(300));
+        assert_eq!(truncated.as_str(), format!("a{}", "é".repeat(255)));
+    }
+
+    #[test]
+    #[should_panic(expected = "the id of `A` is 0x")]
+    fn fixed_id_mismatch() {
+        __private::fixed_id("A", <A as FixedTypeId>::TYPE_ID, 0x1234);
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(feature = "erase_name"),
+        should_panic(expected = "the type name of `A` is \"m::A\", expected \"A\"")
+    )]
+    #[cfg_attr(
+        feature = "erase_name",
+        should_panic(expected = "the type name of `A` is \"0x")
+    )]
+    fn type_name_mismatch() {
+        __private::type_name("A", <A as FixedTypeId>::TYPE_NAME, "A");
+    }
+
+    #[test]
+    #[should_panic(expected = "`A` and `u8` have different ids")]
+    fn same_id_mismatch() {
+        __private::same_id(
+            ["A", "u8"],
+            [<A as FixedTypeId>::TYPE_ID, <u8 as FixedTypeId>::TYPE_ID],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`A` and `B` have the same id")]
+    fn distinct_ids_mismatch() {
+        __private::distinct_ids(
+            &["u8", "A", "B"],
+            &[
+                <u8 as FixedTypeId>::TYPE_ID,
+                <A as FixedTypeId>::TYPE_ID,
+                <B as FixedTypeId>::TYPE_ID,
+            ],
+        );
+    }
+}
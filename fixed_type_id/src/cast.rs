@@ -0,0 +1,385 @@
+//! Cast a `dyn FixedAny` to the trait objects its underlying type implements.
+//!
+//! When feature `cast` is enabled, concrete types can register which `dyn Trait` they implement with
+//! [`register_casts!`](crate::register_casts!). Registrations are collected into [`CAST_REGISTRY`] at link time,
+//! keyed by the [`FixedId`] of the concrete type and `<dyn Trait as FixedTypeId>::TYPE_ID`, so both the concrete type
+//! and the trait object must implement [`FixedTypeId`].
+//!
+//! ```rust
+//! # #![cfg_attr(feature = "specialization", feature(specialization))]
+//! mod shapes {
+//!     use fixed_type_id::prelude::*;
+//!
+//!     pub trait Shape {
+//!         fn area(&self) -> f64;
+//!     }
+//!
+//!     pub struct Square(pub f64);
+//!
+//!     impl Shape for Square {
+//!         fn area(&self) -> f64 {
+//!             self.0 * self.0
+//!         }
+//!     }
+//!
+//!     fixed_type_id! {
+//!         shapes::Square;
+//!         dyn shapes::Shape;
+//!     }
+//!
+//!     fixed_type_id::register_casts!(Square => dyn Shape);
+//! }
+//! use fixed_type_id::FixedAny;
+//! use shapes::*;
+//!
+//! let value: Box<dyn FixedAny> = Box::new(Square(2.0));
+//! assert_eq!(value.cast_ref::<dyn Shape>().unwrap().area(), 4.0);
+//! let shape: Box<dyn Shape> = value.cast::<dyn Shape>().ok().unwrap();
+//! assert_eq!(shape.area(), 4.0);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use linkme::distributed_slice;
+
+use crate::{ConstTypeName, FixedAny, FixedId, FixedTypeId};
+
+/// Cast a box of `dyn FixedAny` to a box of `Trait`, returns the box back on failure.
+pub type BoxCaster<Trait> = fn(Box<dyn FixedAny>) -> Result<Box<Trait>, Box<dyn FixedAny>>;
+
+/// The functions which cast a `dyn FixedAny` to `Trait`, usually a `dyn Trait`.
+///
+/// Each function returns `None` or the input back if the underlying type is not the registered one.
+pub struct Caster<Trait: ?Sized + 'static> {
+    /// Cast a shared reference.
+    pub cast_ref: fn(&dyn FixedAny) -> Option<&Trait>,
+    /// Cast a mutable reference.
+    pub cast_mut: fn(&mut dyn FixedAny) -> Option<&mut Trait>,
+    /// Cast a box.
+    pub cast_box: BoxCaster<Trait>,
+}
+
+impl<Trait: ?Sized + FixedTypeId> FixedTypeId for Caster<Trait> {
+    const TYPE_NAME: &'static str = Self::TYPE_NAME_STR;
+}
+
+impl<Trait: ?Sized + FixedTypeId> ConstTypeName for Caster<Trait> {
+    const RAW_SLICE: &'static [&'static str] =
+        &["fixed_type_id::cast::Caster<", Trait::TYPE_NAME, ">"];
+}
+
+/// A registered cast from a concrete type to a trait object.
+#[derive(Clone, Copy)]
+pub struct CastEntry {
+    /// The [`FixedTypeId::TYPE_ID`] of the concrete type.
+    pub source: FixedId,
+    /// The [`FixedTypeId::TYPE_ID`] of the trait object.
+    pub target: FixedId,
+    /// The [`Caster`] of the trait object, type erased.
+    pub caster: &'static (dyn FixedAny + Send + Sync),
+}
+
+impl CastEntry {
+    /// Create the entry of casting `T` to `Trait`, can be used in const context.
+    pub const fn new<T: FixedTypeId, Trait: ?Sized + FixedTypeId>(
+        caster: &'static Caster<Trait>,
+    ) -> Self {
+        CastEntry {
+            source: T::TYPE_ID,
+            target: Trait::TYPE_ID,
+            caster,
+        }
+    }
+
+    /// Get the [`Caster`] if the target of this entry is `Trait`.
+    pub fn caster<Trait: ?Sized + FixedTypeId>(&self) -> Option<&'static Caster<Trait>> {
+        if self.target == Trait::TYPE_ID {
+            self.caster.downcast_ref()
+        } else {
+            None
+        }
+    }
+}
+
+/// All registered casts in the final binary, in an unspecified order.
+#[distributed_slice]
+pub static CAST_REGISTRY: [CastEntry];
+
+#[doc(hidden)]
+pub mod __private {
+    pub use linkme::{self, distributed_slice};
+}
+
+/// Register the trait objects a concrete type can be cast to.
+///
+/// ```rust,ignore
+/// fixed_type_id::register_casts!(
+///     Square => dyn Shape, dyn Display;
+///     Circle => dyn Shape;
+/// );
+/// ```
+#[macro_export]
+macro_rules! register_casts {
+    ($($ty:ty => $($target:ty),+);* $(;)?) => {
+        $($(
+            const _: () = {
+                // the object lifetime of `dyn Trait` defaults to `'static` in a type alias
+                type Target = $target;
+
+                fn cast_ref(any: &dyn $crate::FixedAny) -> Option<&Target> {
+                    any.downcast_ref::<$ty>().map(|value| value as &Target)
+                }
+                fn cast_mut(any: &mut dyn $crate::FixedAny) -> Option<&mut Target> {
+                    any.downcast_mut::<$ty>().map(|value| value as &mut Target)
+                }
+                fn cast_box(
+                    any: Box<dyn $crate::FixedAny>,
+                ) -> Result<Box<Target>, Box<dyn $crate::FixedAny>> {
+                    any.downcast::<$ty>().map(|value| value as Box<Target>)
+                }
+
+                #[$crate::cast::__private::distributed_slice($crate::cast::CAST_REGISTRY)]
+                #[linkme(crate = $crate::cast::__private::linkme)]
+                static ENTRY: $crate::cast::CastEntry = $crate::cast::CastEntry::new::<$ty, Target>(
+                    &$crate::cast::Caster::<Target> {
+                        cast_ref,
+                        cast_mut,
+                        cast_box,
+                    },
+                );
+            };
+        )+)*
+    };
+}
+
+fn index() -> &'static HashMap<(FixedId, FixedId), Vec<&'static CastEntry>> {
+    static INDEX: OnceLock<HashMap<(FixedId, FixedId), Vec<&'static CastEntry>>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let mut index: HashMap<(FixedId, FixedId), Vec<&'static CastEntry>> =
+            HashMap::with_capacity(CAST_REGISTRY.len());
+        for entry in CAST_REGISTRY.iter() {
+            index
+                .entry((entry.source, entry.target))
+                .or_default()
+                .push(entry);
+        }
+        index
+    })
+}
+
+/// Find every registered cast from the concrete type `source` to the trait object `target`.
+///
+/// There is more than one entry if types sharing an id, like types declared with `equal_to`, register the same trait object.
+pub fn lookup(source: FixedId, target: FixedId) -> &'static [&'static CastEntry] {
+    index().get(&(source, target)).map_or(&[], Vec::as_slice)
+}
+
+/// Returns `true` if the concrete type `source` is registered to be cast to the trait object `target`.
+pub fn can_cast(source: FixedId, target: FixedId) -> bool {
+    index().contains_key(&(source, target))
+}
+
+/// Iterate over all registered casts.
+pub fn iter() -> impl Iterator<Item = &'static CastEntry> {
+    CAST_REGISTRY.iter()
+}
+
+// the casters registered for the id of `any`, only one of them accepts the underlying type
+fn caster<Trait: ?Sized + FixedTypeId>(any: &dyn FixedAny) -> Option<&'static Caster<Trait>> {
+    lookup(any.fixed_id(), Trait::TYPE_ID)
+        .iter()
+        .filter_map(|entry| entry.caster::<Trait>())
+        .find(|caster| (caster.cast_ref)(any).is_some())
+}
+
+/// Implement the casting methods for `dyn FixedAny` and its `Send`, `Sync` variants.
+macro_rules! impl_fixed_any_cast {
+    ($($dyn_type:ty),*) => {
+        $(
+            impl $dyn_type {
+                /// Returns `true` if the underlying type is registered to be cast to `Trait`.
+                #[inline]
+                pub fn can_cast<Trait: ?Sized + FixedTypeId + 'static>(&self) -> bool {
+                    caster::<Trait>(self).is_some()
+                }
+
+                /// Cast to a reference of the trait object `Trait`, if the underlying type is registered to implement it.
+                #[inline]
+                pub fn cast_ref<Trait: ?Sized + FixedTypeId + 'static>(&self) -> Option<&Trait> {
+                    (caster::<Trait>(self)?.cast_ref)(self)
+                }
+
+                /// Cast to a mutable reference of the trait object `Trait`, if the underlying type is registered to implement it.
+                #[inline]
+                pub fn cast_mut<Trait: ?Sized + FixedTypeId + 'static>(&mut self) -> Option<&mut Trait> {
+                    (caster::<Trait>(&*self)?.cast_mut)(self)
+                }
+
+                /// Cast the box to a box of the trait object `Trait`, returns the box back if the underlying type
+                /// is not registered to implement it.
+                #[inline]
+                pub fn cast<Trait: ?Sized + FixedTypeId + 'static>(
+                    self: Box<Self>,
+                ) -> Result<Box<Trait>, Box<Self>> {
+                    // the box can't be given back as `Box<Self>` once erased, so the caster is checked by reference first
+                    match caster::<Trait>(&*self) {
+                        Some(caster) => match (caster.cast_box)(self) {
+                            Ok(value) => Ok(value),
+                            Err(_) => unreachable!("the underlying type was checked by `cast_ref`"),
+                        },
+                        None => Err(self),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_any_cast!(
+    dyn FixedAny,
+    dyn FixedAny + Send,
+    dyn FixedAny + Send + Sync
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::cast::{can_cast, iter, lookup};
+    use crate::prelude::*;
+    use crate::FixedAny;
+
+    mod shapes {
+        use crate::prelude::*;
+
+        pub trait Shape {
+            fn area(&self) -> f64;
+            fn scale(&mut self, factor: f64);
+        }
+
+        pub trait Named {
+            fn name(&self) -> &'static str;
+        }
+
+        pub struct Square(pub f64);
+        pub struct Circle(pub f64);
+        pub struct Rect(pub f64, pub f64);
+
+        impl Shape for Square {
+            fn area(&self) -> f64 {
+                self.0 * self.0
+            }
+            fn scale(&mut self, factor: f64) {
+                self.0 *= factor;
+            }
+        }
+
+        impl Named for Square {
+            fn name(&self) -> &'static str {
+                "square"
+            }
+        }
+
+        impl Shape for Circle {
+            fn area(&self) -> f64 {
+                3.0 * self.0 * self.0
+            }
+            fn scale(&mut self, factor: f64) {
+                self.0 *= factor;
+            }
+        }
+
+        impl Shape for Rect {
+            fn area(&self) -> f64 {
+                self.0 * self.1
+            }
+            fn scale(&mut self, factor: f64) {
+                self.0 *= factor;
+                self.1 *= factor;
+            }
+        }
+
+        fixed_type_id! {
+            tests::cast::Square;
+            tests::cast::Circle;
+            dyn tests::cast::Shape;
+            dyn tests::cast::Named;
+        }
+        fixed_type_id! {
+            #[equal_to(Square)]
+            tests::cast::Rect;
+        }
+
+        crate::register_casts!(
+            Square => dyn Shape, dyn Named;
+            Circle => dyn Shape;
+            Rect => dyn Shape;
+        );
+    }
+    use shapes::*;
+
+    #[test]
+    fn cast_trait_objects() {
+        let mut square: Box<dyn FixedAny> = Box::new(Square(2.0));
+        assert!(square.can_cast::<dyn Shape>());
+        assert!(square.can_cast::<dyn Named>());
+        assert_eq!(square.cast_ref::<dyn Shape>().unwrap().area(), 4.0);
+        assert_eq!(square.cast_ref::<dyn Named>().unwrap().name(), "square");
+        square.cast_mut::<dyn Shape>().unwrap().scale(2.0);
+        assert_eq!(square.downcast_ref::<Square>().unwrap().0, 4.0);
+
+        let circle: Box<dyn FixedAny> = Box::new(Circle(1.0));
+        assert!(!circle.can_cast::<dyn Named>());
+        assert!(circle.cast_ref::<dyn Named>().is_none());
+        let circle = circle.cast::<dyn Named>().err().unwrap();
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            square.cast::<dyn Shape>().ok().unwrap(),
+            circle.cast::<dyn Shape>().ok().unwrap(),
+        ];
+        assert_eq!(shapes.iter().map(|shape| shape.area()).sum::<f64>(), 19.0);
+
+        // not registered at all
+        let value: Box<dyn FixedAny> = Box::new(1u8);
+        assert!(value.cast_ref::<dyn Shape>().is_none());
+
+        let mut square: Box<dyn FixedAny + Send> = Box::new(Square(1.0));
+        square.cast_mut::<dyn Shape>().unwrap().scale(3.0);
+        let square = square.cast::<dyn Named>().ok().unwrap();
+        assert_eq!(square.name(), "square");
+        let circle: Box<dyn FixedAny + Send + Sync> = Box::new(Circle(1.0));
+        assert!(circle.can_cast::<dyn Shape>());
+        let circle: Box<dyn FixedAny + Send + Sync> = circle.cast::<dyn Named>().err().unwrap();
+        assert_eq!(circle.cast_ref::<dyn Shape>().unwrap().area(), 3.0);
+    }
+
+    #[test]
+    fn lookup_casts() {
+        let square = <Square as FixedTypeId>::TYPE_ID;
+        let shape = <dyn Shape as FixedTypeId>::TYPE_ID;
+        let entry = lookup(square, shape)[0];
+        assert!(entry.caster::<dyn Shape>().is_some());
+        assert!(entry.caster::<dyn Named>().is_none());
+        assert!(can_cast(square, <dyn Named as FixedTypeId>::TYPE_ID));
+        assert!(!can_cast(shape, square));
+        assert!(lookup(shape, square).is_empty());
+        assert_eq!(iter().filter(|entry| entry.target == shape).count(), 3);
+    }
+
+    #[test]
+    fn cast_same_id() {
+        // `Rect` shares the id of `Square`, both casts are kept
+        let square = <Square as FixedTypeId>::TYPE_ID;
+        assert_eq!(<Rect as FixedTypeId>::TYPE_ID, square);
+        assert_eq!(lookup(square, <dyn Shape as FixedTypeId>::TYPE_ID).len(), 2);
+
+        let mut rect: Box<dyn FixedAny> = Box::new(Rect(1.0, 2.0));
+        assert!(rect.can_cast::<dyn Shape>());
+        assert!(!rect.can_cast::<dyn Named>());
+        rect.cast_mut::<dyn Shape>().unwrap().scale(2.0);
+        assert_eq!(rect.cast_ref::<dyn Shape>().unwrap().area(), 8.0);
+        let rect = rect.cast::<dyn Named>().err().unwrap();
+        assert_eq!(rect.cast::<dyn Shape>().ok().unwrap().area(), 8.0);
+
+        let square: Box<dyn FixedAny> = Box::new(Square(3.0));
+        assert_eq!(square.cast::<dyn Shape>().ok().unwrap().area(), 9.0);
+    }
+}
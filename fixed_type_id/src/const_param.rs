@@ -0,0 +1,140 @@
+//! Render the values of const generic parameters as `&'static str` in const context.
+//!
+//! Used to build the type names of types with const generic parameters, like `[T; N]`,
+//! so every value gets its own [`FixedTypeId::TYPE_NAME`](crate::FixedTypeId::TYPE_NAME) and [`FixedId`](crate::FixedId).
+//!
+//! ```rust
+//! use fixed_type_id::const_param::{ConstBool, ConstI32, ConstUsize};
+//!
+//! assert_eq!(ConstUsize::<100>::STR, "100");
+//! assert_eq!(ConstI32::<-42>::STR, "-42");
+//! assert_eq!(ConstBool::<true>::STR, "true");
+//! ```
+
+use crate::const_str::bytes_to_str;
+
+/// The max length of a rendered integer, the sign and 39 digits of [`u128::MAX`].
+const MAX_INT_LEN: usize = 40;
+
+/// Write the decimal digits of an integer into a buffer, returns the buffer and the length.
+const fn int_to_bytes(negative: bool, mut magnitude: u128) -> ([u8; MAX_INT_LEN], usize) {
+    let mut digits = [0u8; MAX_INT_LEN];
+    let mut len = 0;
+    loop {
+        digits[len] = b'0' + (magnitude % 10) as u8;
+        len += 1;
+        magnitude /= 10;
+        if magnitude == 0 {
+            break;
+        }
+    }
+    if negative {
+        digits[len] = b'-';
+        len += 1;
+    }
+    // the digits are written in reverse order
+    let mut bytes = [0u8; MAX_INT_LEN];
+    let mut i = 0;
+    while i < len {
+        bytes[i] = digits[len - 1 - i];
+        i += 1;
+    }
+    (bytes, len)
+}
+
+/// Write a char as UTF-8 between single quotes into a buffer, returns the buffer and the length.
+const fn quoted_char_to_bytes(c: char) -> ([u8; 6], usize) {
+    let mut bytes = [0u8; 6];
+    let (_, rest) = bytes.split_at_mut(1);
+    let len = c.encode_utf8(rest).len();
+    bytes[0] = b'\'';
+    bytes[len + 1] = b'\'';
+    (bytes, len + 2)
+}
+
+/// Implement the rendering of integer types, `$negative` tells whether `N` is negative,
+/// and `$magnitude` converts `N` to its absolute value in `u128`.
+macro_rules! impl_const_int {
+    (|$n:ident| $negative:expr, $magnitude:expr; $($name:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Render a const `", stringify!($ty), "` parameter `N` as a `&'static str`.")]
+            pub struct $name<const N: $ty>;
+
+            impl<const N: $ty> $name<N> {
+                const RENDERED: ([u8; MAX_INT_LEN], usize) = {
+                    let $n = N;
+                    int_to_bytes($negative, $magnitude)
+                };
+                const BYTES: &'static [u8] = &Self::RENDERED.0;
+                /// The decimal representation of `N`.
+                pub const STR: &'static str = bytes_to_str(Self::BYTES, Self::RENDERED.1);
+            }
+        )*
+    };
+}
+
+impl_const_int!(
+    |n| false, n as u128;
+    ConstUsize: usize,
+    ConstU8: u8,
+    ConstU16: u16,
+    ConstU32: u32,
+    ConstU64: u64,
+    ConstU128: u128,
+);
+
+impl_const_int!(
+    |n| n < 0, (n as i128).unsigned_abs();
+    ConstIsize: isize,
+    ConstI8: i8,
+    ConstI16: i16,
+    ConstI32: i32,
+    ConstI64: i64,
+    ConstI128: i128,
+);
+
+/// Render a const `bool` parameter `B` as a `&'static str`.
+pub struct ConstBool<const B: bool>;
+
+impl<const B: bool> ConstBool<B> {
+    /// `"true"` or `"false"`.
+    pub const STR: &'static str = if B { "true" } else { "false" };
+}
+
+/// Render a const `char` parameter `C` as a `&'static str`.
+pub struct ConstChar<const C: char>;
+
+impl<const C: char> ConstChar<C> {
+    const RENDERED: ([u8; 6], usize) = quoted_char_to_bytes(C);
+    const BYTES: &'static [u8] = &Self::RENDERED.0;
+    /// The char quoted like a char literal, e.g. `'a'`.
+    pub const STR: &'static str = bytes_to_str(Self::BYTES, Self::RENDERED.1);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::const_param::*;
+
+    #[test]
+    fn render_integers() {
+        assert_eq!(ConstUsize::<0>::STR, "0");
+        assert_eq!(ConstUsize::<33>::STR, "33");
+        assert_eq!(ConstUsize::<100>::STR, "100");
+        assert_eq!(ConstUsize::<{ usize::MAX }>::STR, usize::MAX.to_string());
+        assert_eq!(ConstU8::<255>::STR, "255");
+        assert_eq!(ConstU128::<{ u128::MAX }>::STR, u128::MAX.to_string());
+        assert_eq!(ConstI8::<{ i8::MIN }>::STR, "-128");
+        assert_eq!(ConstI32::<-42>::STR, "-42");
+        assert_eq!(ConstI64::<42>::STR, "42");
+        assert_eq!(ConstI128::<{ i128::MIN }>::STR, i128::MIN.to_string());
+        assert_eq!(ConstIsize::<0>::STR, "0");
+    }
+
+    #[test]
+    fn render_bool_char() {
+        assert_eq!(ConstBool::<true>::STR, "true");
+        assert_eq!(ConstBool::<false>::STR, "false");
+        assert_eq!(ConstChar::<'a'>::STR, "'a'");
+        assert_eq!(ConstChar::<'中'>::STR, "'中'");
+    }
+}
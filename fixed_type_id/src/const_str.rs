@@ -0,0 +1,55 @@
+//! Build `&'static str`s in const context, for [`const_param`](crate::const_param) and the names erased by feature `erase_name`.
+
+/// Convert the first `len` bytes to a `&str`.
+pub const fn bytes_to_str(bytes: &'static [u8], len: usize) -> &'static str {
+    let (s, _) = bytes.split_at(len);
+    match core::str::from_utf8(s) {
+        Ok(s) => s,
+        Err(_) => panic!("the rendered bytes are not valid UTF-8"),
+    }
+}
+
+/// The max length of a type name erased by feature `erase_name`, `0x` and the 16 hex digits of a `u64`.
+pub const ERASED_NAME_LEN: usize = 18;
+
+/// Erase a type name like feature `erase_name` does, into `0x` and the hex digits of its rapidhash,
+/// returns the buffer and the length.
+pub const fn erase_name(name: &str) -> ([u8; ERASED_NAME_LEN], usize) {
+    let hash = crate::algorithm::rapidhash::rapidhash(name.as_bytes());
+    let mut bytes = [0u8; ERASED_NAME_LEN];
+    bytes[0] = b'0';
+    bytes[1] = b'x';
+    let mut digits = 1;
+    while digits < 16 && hash >> (4 * digits) != 0 {
+        digits += 1;
+    }
+    let mut i = 0;
+    while i < digits {
+        let digit = ((hash >> (4 * (digits - 1 - i))) & 0xf) as u8;
+        bytes[2 + i] = if digit < 10 {
+            b'0' + digit
+        } else {
+            b'a' + digit - 10
+        };
+        i += 1;
+    }
+    (bytes, 2 + digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::const_str::*;
+
+    #[test]
+    fn erase_names() {
+        use crate::algorithm::rapidhash::rapidhash;
+
+        for name in ["", "a", "my_crate::MyType", "0"] {
+            let (bytes, len) = erase_name(name);
+            assert_eq!(
+                core::str::from_utf8(&bytes[..len]).unwrap(),
+                format!("0x{:x}", rapidhash(name.as_bytes()))
+            );
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! A hasher for [`FixedId`] keys, and collections using it.
+//!
+//! [`FixedId`] is already a hash, and its `Hash` implementation only writes the inner `u64`
+//! with [`Hasher::write_u64`], so there is no need to hash it again with SipHash.
+//! [`FixedIdHasher`] passes the `u64` straight through.
+//!
+//! ```rust
+//! # #[cfg(feature = "std")] {
+//! use fixed_type_id::prelude::*;
+//! use fixed_type_id::hasher::{FixedIdMap, FixedIdSet};
+//!
+//! let mut names = FixedIdMap::default();
+//! names.insert(<u8 as FixedTypeId>::TYPE_ID, "u8");
+//! assert_eq!(names[&<u8 as FixedTypeId>::TYPE_ID], "u8");
+//!
+//! let mut seen = FixedIdSet::default();
+//! assert!(seen.insert(<u8 as FixedTypeId>::TYPE_ID));
+//! assert!(!seen.insert(<u8 as FixedTypeId>::TYPE_ID));
+//! # }
+//! ```
+
+use core::hash::{BuildHasherDefault, Hasher};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+#[cfg(any(feature = "std", feature = "indexmap"))]
+use crate::FixedId;
+
+/// A [`Hasher`] which passes the `u64` written by [`FixedId`]'s `Hash` implementation through.
+///
+/// # Panics
+///
+/// Panics if anything other than a single `u64` is written, so only use it for [`FixedId`] keys,
+/// not for keys containing a [`FixedId`], like `(FixedId, FixedId)`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FixedIdHasher(Option<u64>);
+
+impl Hasher for FixedIdHasher {
+    #[inline]
+    fn write(&mut self, _bytes: &[u8]) {
+        panic!("FixedIdHasher can only hash `FixedId`, which is written with `write_u64`");
+    }
+
+    #[inline]
+    fn write_u64(&mut self, id: u64) {
+        if self.0.replace(id).is_some() {
+            panic!("FixedIdHasher can only hash a single `FixedId`, but a second `u64` is written");
+        }
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0.unwrap_or_default()
+    }
+}
+
+/// A [`core::hash::BuildHasher`] which builds [`FixedIdHasher`].
+pub type BuildFixedIdHasher = BuildHasherDefault<FixedIdHasher>;
+
+/// A [`HashMap`] keyed by [`FixedId`], using [`FixedIdHasher`].
+#[cfg(feature = "std")]
+pub type FixedIdMap<V> = HashMap<FixedId, V, BuildFixedIdHasher>;
+
+/// A [`HashSet`] of [`FixedId`], using [`FixedIdHasher`].
+#[cfg(feature = "std")]
+pub type FixedIdSet = HashSet<FixedId, BuildFixedIdHasher>;
+
+/// An [`indexmap::IndexMap`] keyed by [`FixedId`], using [`FixedIdHasher`], which keeps the insertion order.
+#[cfg(feature = "indexmap")]
+pub type FixedIdIndexMap<V> = indexmap::IndexMap<FixedId, V, BuildFixedIdHasher>;
+
+#[cfg(test)]
+mod tests {
+    use core::hash::Hash;
+
+    use crate::hasher::FixedIdHasher;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn pass_through() {
+        use core::hash::BuildHasher;
+
+        use crate::hasher::{BuildFixedIdHasher, FixedIdMap, FixedIdSet};
+        use crate::prelude::*;
+
+        let id = <String as FixedTypeId>::TYPE_ID;
+        assert_eq!(BuildFixedIdHasher::default().hash_one(id), id.as_u64());
+
+        let mut map = FixedIdMap::default();
+        map.insert(id, 1);
+        map.insert(<u8 as FixedTypeId>::TYPE_ID, 2);
+        assert_eq!(map.get(&id), Some(&1));
+        let set: FixedIdSet = map.keys().copied().collect();
+        assert!(set.contains(&<u8 as FixedTypeId>::TYPE_ID));
+    }
+
+    #[test]
+    #[should_panic(expected = "FixedIdHasher can only hash `FixedId`")]
+    fn panic_on_other_writes() {
+        let mut hasher = FixedIdHasher::default();
+        "not an id".hash(&mut hasher);
+    }
+
+    #[test]
+    #[should_panic(expected = "FixedIdHasher can only hash a single `FixedId`")]
+    fn panic_on_tuple_keys() {
+        use crate::prelude::*;
+
+        let mut hasher = FixedIdHasher::default();
+        (<u8 as FixedTypeId>::TYPE_ID, <u16 as FixedTypeId>::TYPE_ID).hash(&mut hasher);
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn index_map() {
+        use crate::hasher::FixedIdIndexMap;
+        use crate::prelude::*;
+
+        let mut map = FixedIdIndexMap::default();
+        map.insert(<u16 as FixedTypeId>::TYPE_ID, "u16");
+        map.insert(<u8 as FixedTypeId>::TYPE_ID, "u8");
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), ["u16", "u8"]);
+    }
+}
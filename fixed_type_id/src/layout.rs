@@ -0,0 +1,127 @@
+//! Hash the structure of a type into [`FixedTypeId::TYPE_LAYOUT_HASH`], in const context.
+//!
+//! `#[derive(FixedTypeId)]` with `#[fixed_type_id(layout)]` generates it with [`LayoutHasher`],
+//! so adding, removing, renaming, reordering or retyping a field changes the hash, even if the version wasn't bumped.
+//! Store the hash along with persisted data, and check it with [`FixedTypeId::layout_matches`] when loading it.
+//! `fixed_revision` stores it in the `FixedTypeIdTagged` header, and checks it when deserializing.
+//!
+//! The hash includes `size_of` and `align_of` of the type and its fields, so it differs between targets,
+//! like a 32-bit and a 64-bit one when a field is a `usize` or a pointer, and for types without `#[repr(C)]`,
+//! it may differ between rustc versions. Only compare hashes computed for the same target and toolchain.
+//!
+//! ```rust
+//! # #![cfg_attr(feature = "specialization", feature(specialization))]
+//! mod v1 {
+//!     use fixed_type_id::prelude::*;
+//!
+//!     #[derive(FixedTypeId)]
+//!     #[fixed_type_id(name = "my_crate::Config", layout)]
+//!     pub struct Config {
+//!         pub width: u32,
+//!     }
+//! }
+//!
+//! mod v2 {
+//!     use fixed_type_id::prelude::*;
+//!
+//!     // a field added without a version bump
+//!     #[derive(FixedTypeId)]
+//!     #[fixed_type_id(name = "my_crate::Config", layout)]
+//!     pub struct Config {
+//!         pub width: u32,
+//!         pub height: u32,
+//!     }
+//! }
+//!
+//! use fixed_type_id::FixedTypeId;
+//!
+//! assert_eq!(<v1::Config as FixedTypeId>::TYPE_ID, <v2::Config as FixedTypeId>::TYPE_ID);
+//! let stored = <v1::Config as FixedTypeId>::TYPE_LAYOUT_HASH;
+//! assert!(<v1::Config as FixedTypeId>::layout_matches(stored));
+//! assert!(!<v2::Config as FixedTypeId>::layout_matches(stored));
+//! ```
+//!
+//! Implement it by hand the same way:
+//!
+//! ```rust
+//! # #![cfg_attr(feature = "specialization", feature(specialization))]
+//! use fixed_type_id::layout::LayoutHasher;
+//! use fixed_type_id::prelude::*;
+//!
+//! pub struct Point {
+//!     pub x: f32,
+//!     pub y: f32,
+//! }
+//!
+//! impl FixedTypeId for Point {
+//!     const TYPE_NAME: &'static str = "Point";
+//!     const TYPE_LAYOUT_HASH: u64 = LayoutHasher::of::<Self>()
+//!         .field::<f32>("x")
+//!         .field::<f32>("y")
+//!         .finish();
+//! }
+//!
+//! assert_ne!(<Point as FixedTypeId>::TYPE_LAYOUT_HASH, <Point as FixedTypeId>::TYPE_ID.as_u64());
+//! ```
+
+use crate::algorithm::FixedIdAlgorithm;
+use crate::{FixedTypeId, IdAlgorithm};
+
+/// Build a [`FixedTypeId::TYPE_LAYOUT_HASH`] in const context, from the size and alignment of a type,
+/// and its fields and enum variants, in declaration order.
+///
+/// The hash is computed with [`IdAlgorithm`], like [`FixedId`](crate::FixedId).
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutHasher {
+    hash: u64,
+}
+
+/// Written before the parts of the hash, so a field can't be mistaken for a variant.
+const FIELD_TAG: u64 = 1;
+const VARIANT_TAG: u64 = 2;
+
+impl LayoutHasher {
+    /// Start hashing the layout of `T`, with its size and alignment.
+    pub const fn of<T>() -> Self {
+        LayoutHasher {
+            hash: IdAlgorithm::hash_seeded(&[], IdAlgorithm::SEED),
+        }
+        .write_u64(size_of::<T>() as u64)
+        .write_u64(align_of::<T>() as u64)
+    }
+
+    /// Hash a field of type `T`, with its name, or its index for a tuple field, and the 64-bit [`FixedTypeId::TYPE_ID`],
+    /// [`FixedTypeId::TYPE_LAYOUT_HASH`], size and alignment of `T`.
+    pub const fn field<T: FixedTypeId>(self, name: &str) -> Self {
+        self.write_u64(FIELD_TAG)
+            .write_bytes(name.as_bytes())
+            .write_u64(T::TYPE_ID.as_u64())
+            .write_u64(T::TYPE_LAYOUT_HASH)
+            .write_u64(size_of::<T>() as u64)
+            .write_u64(align_of::<T>() as u64)
+    }
+
+    /// Hash an enum variant by its name, its fields follow it.
+    pub const fn variant(self, name: &str) -> Self {
+        self.write_u64(VARIANT_TAG).write_bytes(name.as_bytes())
+    }
+
+    /// Get the layout hash.
+    pub const fn finish(self) -> u64 {
+        self.hash
+    }
+
+    const fn write_bytes(self, bytes: &[u8]) -> Self {
+        LayoutHasher {
+            hash: IdAlgorithm::combine(
+                self.hash,
+                IdAlgorithm::hash_seeded(bytes, IdAlgorithm::SEED),
+                IdAlgorithm::SEED,
+            ),
+        }
+    }
+
+    const fn write_u64(self, value: u64) -> Self {
+        self.write_bytes(&value.to_le_bytes())
+    }
+}
@@ -1,42 +1,135 @@
 #![deny(missing_docs)]
-#![allow(internal_features)]
 #![allow(incomplete_features)]
-#![feature(str_from_raw_parts)]
-#![feature(generic_const_exprs)]
-#![feature(nonzero_internals)]
 #![cfg_attr(feature = "specialization", feature(specialization))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![doc = include_str!("../README.md")]
 
+extern crate alloc;
+// the registration code generated by the macros refers to `::fixed_type_id`, also inside this crate
+extern crate self as fixed_type_id;
+
+mod any;
+pub mod assert;
+#[cfg(feature = "cast")]
+pub mod cast;
+pub mod const_param;
+mod const_str;
+pub mod hasher;
+pub mod layout;
+#[cfg(feature = "std")]
+pub mod manifest;
+#[cfg(feature = "registry")]
+pub mod registry;
 mod remote_impl;
+#[cfg(feature = "std")]
+pub mod type_map;
+pub mod version;
 
 use core::fmt;
-use std::hash::Hash;
+use core::hash::Hash;
+
+use algorithm::FixedIdAlgorithm;
+pub use fixed_type_id_algorithm as algorithm;
 
 /// Prelude used with [`fixed_type_id`]
 pub mod prelude {
     // used with macros defines
-    pub use super::fixed_type_id;
-    pub use super::{fstr_to_str, ConstTypeName, FixedId, FixedTypeId, FixedVersion};
+    #[cfg(feature = "std")]
+    pub use super::fstr_to_str;
+    pub use super::{ConstTypeName, FixedId, FixedTypeId, FixedVersion};
+    pub use fixed_type_id_macros::fixed_type_id;
     // standalone functions
     pub use super::{type_id, type_name, type_version};
+    // used by the generated code to render const generic parameters
+    #[doc(hidden)]
+    pub use super::const_param as __fixed_type_id_const_param;
+    // used by the generated code to erase the names with feature `erase_name`
+    #[doc(hidden)]
+    pub mod __fixed_type_id_const_str {
+        pub use crate::const_str::{bytes_to_str, erase_name, ERASED_NAME_LEN};
+    }
+    // used by the generated code of `#[fixed_type_id(layout)]`
+    #[doc(hidden)]
+    pub use super::layout as __fixed_type_id_layout;
+    // used by the generated code of `#[version(rustc)]`
+    #[doc(hidden)]
+    pub use super::RUSTC_VERSION as __fixed_type_id_rustc_version;
 }
 
-pub use fixed_type_id_macros::fixed_type_id;
+pub use any::FixedAny;
+pub use fixed_type_id_macros::{fixed_type_id, FixedTypeId};
 use semver::Version;
+#[cfg(feature = "std")]
+pub use type_map::FixedTypeMap;
+pub use version::FixedPrerelease;
+
+/// The max length of a type name generated by [`ConstTypeName`] in const context, longer names fail to compile.
+pub const MAX_CONST_TYPENAME_LEN: usize = 4096;
+
+/// The length of the deprecated [`ConstTypeName::TYPE_NAME_FSTR`], the same as [`MAX_CONST_TYPENAME_LEN`].
+///
+/// The feature flags `len64`, `len128` and `len256` don't change it anymore, they are kept as no-op for compatibility.
+#[deprecated(note = "type names are sized by their length, up to `MAX_CONST_TYPENAME_LEN`")]
+pub const CONST_TYPENAME_LEN: usize = MAX_CONST_TYPENAME_LEN;
 
-/// The length of the type name, can be configured by feature flags `len128`, `len64` and `len256`, the default is `len128`.
-#[cfg(feature = "len128")]
-pub const CONST_TYPENAME_LEN: usize = 128;
+/// The version of the rustc compiling this crate with feature `rustc_version`, `0.0.0` without it.
+///
+/// It's the [`FixedTypeId::TYPE_VERSION`] of the standard library types, and of the types with `#[version(rustc)]`,
+/// their [`FixedTypeId::TYPE_ID`] is always hashed with version `0.0.0`, so it never changes with the toolchain.
+#[cfg(feature = "rustc_version")]
+pub const RUSTC_VERSION: FixedVersion = FixedVersion::new(
+    parse_u64(env!("FIXED_TYPE_ID_RUSTC_MAJOR")),
+    parse_u64(env!("FIXED_TYPE_ID_RUSTC_MINOR")),
+    parse_u64(env!("FIXED_TYPE_ID_RUSTC_PATCH")),
+);
 
-/// The length of the type name, can be configured by feature flags `len128`, `len64` and `len256`, the default is `len128`.
-#[cfg(feature = "len64")]
-pub const CONST_TYPENAME_LEN: usize = 64;
+/// The version of the rustc compiling this crate with feature `rustc_version`, `0.0.0` without it.
+///
+/// It's the [`FixedTypeId::TYPE_VERSION`] of the standard library types, and of the types with `#[version(rustc)]`,
+/// their [`FixedTypeId::TYPE_ID`] is always hashed with version `0.0.0`, so it never changes with the toolchain.
+#[cfg(not(feature = "rustc_version"))]
+pub const RUSTC_VERSION: FixedVersion = FixedVersion::new(0, 0, 0);
 
-/// The length of the type name, can be configured by feature flags `len128`, `len64` and `len256`, the default is `len128`.
-#[cfg(feature = "len256")]
-pub const CONST_TYPENAME_LEN: usize = 256;
+#[cfg(feature = "rustc_version")]
+const fn parse_u64(s: &str) -> u64 {
+    match u64::from_str_radix(s, 10) {
+        Ok(value) => value,
+        Err(_) => panic!("invalid rustc version passed by the build script"),
+    }
+}
+
+/// The inner integer of [`FixedId`], `u64` by default, `u128` when feature `id128` is enabled.
+#[cfg(not(feature = "id128"))]
+pub type RawFixedId = u64;
+
+/// The inner integer of [`FixedId`], `u64` by default, `u128` when feature `id128` is enabled.
+#[cfg(feature = "id128")]
+pub type RawFixedId = u128;
+
+#[cfg(all(feature = "xxh3", feature = "fnv1a"))]
+compile_error!(
+    "features `xxh3` and `fnv1a` select different id algorithms, enable at most one of them"
+);
+
+/// The [`FixedIdAlgorithm`](algorithm::FixedIdAlgorithm) used to generate [`FixedId`], selected by feature flags:
+/// rapidhash by default, XXH3 with feature `xxh3`, FNV-1a with feature `fnv1a`.
+#[cfg(not(any(feature = "xxh3", feature = "fnv1a")))]
+pub type IdAlgorithm = algorithm::Rapidhash;
+
+/// The [`FixedIdAlgorithm`](algorithm::FixedIdAlgorithm) used to generate [`FixedId`], selected by feature flags:
+/// rapidhash by default, XXH3 with feature `xxh3`, FNV-1a with feature `fnv1a`.
+#[cfg(feature = "xxh3")]
+pub type IdAlgorithm = algorithm::Xxh3;
+
+/// The [`FixedIdAlgorithm`](algorithm::FixedIdAlgorithm) used to generate [`FixedId`], selected by feature flags:
+/// rapidhash by default, XXH3 with feature `xxh3`, FNV-1a with feature `fnv1a`.
+#[cfg(all(feature = "fnv1a", not(feature = "xxh3")))]
+pub type IdAlgorithm = algorithm::Fnv1a;
 
 /// A strong type for type id.
+///
+/// It wraps a [`u64`] by default, or a [`u128`] when feature `id128` is enabled, see [`RawFixedId`].
+/// The low 64 bits of a 128-bit id are always the same as the 64-bit id of the type, see [`FixedId::as_u64`].
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 #[cfg_attr(
@@ -46,7 +139,7 @@ pub const CONST_TYPENAME_LEN: usize = 256;
 #[cfg_attr(feature = "rkyv", rkyv(attr(allow(missing_docs))))]
 #[cfg_attr(feature = "rkyv", rkyv(compare(PartialEq), derive(Debug)))]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
-pub struct FixedId(pub u64);
+pub struct FixedId(pub RawFixedId);
 
 #[cfg(feature = "rkyv")]
 impl From<&ArchivedFixedId> for FixedId {
@@ -55,10 +148,12 @@ impl From<&ArchivedFixedId> for FixedId {
     }
 }
 
-/// Just write internal [`u64`] with [`std::hash::Hasher::write_u64`].
+/// Just write internal [`u64`] with [`core::hash::Hasher::write_u64`].
+///
+/// With feature `id128`, only the low 64 bits are written, they are already a well distributed hash.
 impl Hash for FixedId {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        state.write_u64(self.0);
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.as_u64());
     }
 }
 
@@ -68,58 +163,76 @@ impl FixedId {
         Target::TYPE_ID
     }
 
-    /// Get the inner u64 value.
+    /// Get the 64-bit form of the id.
+    ///
+    /// With feature `id128`, it's the low 64 bits, which equal to the id generated without feature `id128`.
+    #[allow(clippy::unnecessary_cast)] // `RawFixedId` is `u128` with feature `id128`
     pub const fn as_u64(&self) -> u64 {
-        self.0
+        self.0 as u64
+    }
+
+    /// Get the id as a [`u128`], a 64-bit id is zero extended.
+    pub const fn as_u128(&self) -> u128 {
+        self.0 as u128
+    }
+
+    /// Create an id from its 64-bit form, see [`FixedId::as_u64`].
+    ///
+    /// With feature `id128`, the high 64 bits can't be recovered and are set to zero,
+    /// so compare the result with other ids by [`FixedId::as_u64`].
+    pub const fn from_u64(id: u64) -> Self {
+        FixedId(id as RawFixedId)
     }
 
     /// Get UniqueId from a type name.
     ///
     /// It can be used inside const context.
     pub const fn from_type_name(type_name: &'static str, version: Option<FixedVersion>) -> Self {
-        let hash = match version {
-            None => rapidhash::rapidhash(type_name.as_bytes()),
-            Some(version) => {
-                // first hash the typename, get a base hash
-                let name_hash = rapidhash::rapidhash(type_name.as_bytes());
-                // then hash the version
-                let version_hash = rapidhash::rapidhash(&version.const_to_bytes());
-                // then combine name_hash and version_hash as a new `&[u8]`
-                //
-                // rapidhash::rapidhash(&u64s_to_bytes(&[name_hash, version_hash]));
-                //
-                // or use rapid_mix
-                rapid_mix(name_hash, version_hash)
-            }
-        };
-        FixedId(hash)
+        FixedId(match &version {
+            Some(version) => hash_name_version(
+                type_name.as_bytes(),
+                Some(version.const_to_bytes()),
+                version.pre.as_bytes(),
+            ),
+            None => hash_name_version(type_name.as_bytes(), None, &[]),
+        })
     }
 }
 
-const fn u64s_to_bytes<const N: usize>(slice: &[u64; N]) -> [u8; N * 8] {
-    let mut bytes = [0u8; N * 8];
-
-    let mut slice_remaining: &[u64] = slice;
-    let mut i = 0;
-    let mut slice_index = 0;
-    while let [current, tail @ ..] = slice_remaining {
-        let mut current_bytes: &[u8] = &current.to_le_bytes();
-        while let [current, tail @ ..] = current_bytes {
-            bytes[i] = *current;
-            i += 1;
-            current_bytes = tail;
-        }
-        slice_index += 1;
-        debug_assert!(i == 8 * slice_index);
-        slice_remaining = tail;
+/// Compute the raw id from the type name, the version bytes and the pre-release bytes, with [`IdAlgorithm`].
+///
+/// A non-empty pre-release is hashed and combined into the id, so the ids of releases don't depend on it.
+const fn hash_name_version(name: &[u8], version: Option<[u8; 24]>, pre: &[u8]) -> RawFixedId {
+    let version = version.as_ref();
+    let low = hash_name_version_seeded(name, version, pre, IdAlgorithm::SEED);
+    #[cfg(feature = "id128")]
+    {
+        let high = hash_name_version_seeded(name, version, pre, algorithm::ID128_HIGH_SEED);
+        ((high as u128) << 64) | low as u128
     }
+    #[cfg(not(feature = "id128"))]
+    low
+}
 
-    bytes
+/// Compute 64 bits of the id with a seed, see [`hash_name_version`].
+const fn hash_name_version_seeded(
+    name: &[u8],
+    version: Option<&[u8; 24]>,
+    pre: &[u8],
+    seed: u64,
+) -> u64 {
+    let hash = IdAlgorithm::hash_name_version(name, version, seed);
+    if pre.is_empty() {
+        hash
+    } else {
+        IdAlgorithm::combine(hash, IdAlgorithm::hash_seeded(pre, seed), seed)
+    }
 }
 
 /// for n <= 32, returns a static string
 /// for n > 32, returns "N"
 /// for special usize, eg 64, 128, 256, 512, 768, 1024, 2048, 4096, 8192, 16384, 32768, 65536, returns a static string
+#[deprecated(note = "use `const_param::ConstUsize::<N>::STR`, which renders every value")]
 pub const fn usize_to_str(n: usize) -> &'static str {
     match n {
         0 => "0",
@@ -171,20 +284,6 @@ pub const fn usize_to_str(n: usize) -> &'static str {
     }
 }
 
-/// Copy from [`rapidhash`]
-#[inline(always)]
-const fn rapid_mum(a: u64, b: u64) -> (u64, u64) {
-    let r = a as u128 * b as u128;
-    (r as u64, (r >> 64) as u64)
-}
-
-/// Copy from [`rapidhash`]
-#[inline(always)]
-const fn rapid_mix(a: u64, b: u64) -> u64 {
-    let (a, b) = rapid_mum(a, b);
-    a ^ b
-}
-
 impl fmt::Display for FixedId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -205,10 +304,22 @@ pub trait FixedTypeId {
     /// It's default use [`FixedId::from_type_name`] with [`Self::TYPE_VERSION`] as additional parameter.
     /// When you want to define an id without version, you can use [`FixedId::from_type_name`] without additional version parameter.
     const TYPE_ID: FixedId = FixedId::from_type_name(Self::TYPE_NAME, Some(Self::TYPE_VERSION));
-    /// A semver for a type, with out pre release, build meta etc.
+    /// A semver for a type, with an optional pre-release, but without build meta.
     ///
     /// Used to check version compatibility. If versions are not compatible, it can be cast to an semver.
     const TYPE_VERSION: FixedVersion = FixedVersion::new(0, 0, 0);
+    /// A fingerprint of the layout of the type, to detect a changed layout without a version bump.
+    ///
+    /// `#[derive(FixedTypeId)]` with `#[fixed_type_id(layout)]` hashes the size and alignment of the type,
+    /// the names, ids, layout hashes, sizes and alignments of its fields, in order, see [`layout::LayoutHasher`].
+    /// For other types, it's the 64-bit [`Self::TYPE_ID`], so it only changes with the name and the version.
+    const TYPE_LAYOUT_HASH: u64 = Self::TYPE_ID.as_u64();
+
+    /// If the layout hash, like one stored along with persisted data, is the [`Self::TYPE_LAYOUT_HASH`] of this type.
+    #[inline(always)]
+    fn layout_matches(layout_hash: u64) -> bool {
+        Self::TYPE_LAYOUT_HASH == layout_hash
+    }
 
     /// Returns the type name.
     #[inline(always)]
@@ -229,7 +340,9 @@ pub trait FixedTypeId {
     }
 }
 
-/// A semver for a type, but without pre release, build meta etc.
+/// A semver for a type, with an optional pre-release, but without build meta.
+///
+/// It's formatted and parsed as `major.minor.patch[-pre]`, see [`FixedVersion::parse`].
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 #[cfg_attr(
     feature = "rkyv",
@@ -245,6 +358,12 @@ pub struct FixedVersion {
     pub minor: u64,
     /// The patch version number.
     pub patch: u64,
+    /// The pre-release, [`FixedPrerelease::EMPTY`] for a release.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "FixedPrerelease::is_empty")
+    )]
+    pub pre: FixedPrerelease,
 }
 
 impl FixedVersion {
@@ -255,17 +374,36 @@ impl FixedVersion {
             major,
             minor,
             patch,
+            pre: FixedPrerelease::EMPTY,
         }
     }
 
+    /// Create a new pre-release `FixedVersion`, like `FixedVersion::new_pre(2, 0, 0, "beta.3")` for `2.0.0-beta.3`.
+    ///
+    /// Panics if `pre` isn't a valid pre-release, see [`FixedPrerelease::new`].
+    pub const fn new_pre(major: u64, minor: u64, patch: u64, pre: &str) -> Self {
+        FixedVersion {
+            major,
+            minor,
+            patch,
+            pre: FixedPrerelease::new(pre),
+        }
+    }
+
+    /// If it's a pre-release version.
+    pub const fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
     /// Get the bytes of the version, can be used in const context.
     ///
-    /// It's slower than [`as_bytes`], but can be used in const context.
+    /// It's slower than [`to_bytes`](Self::to_bytes), but can be used in const context.
+    /// The pre-release isn't included, it's hashed separately.
     pub const fn const_to_bytes(&self) -> [u8; 24] {
-        u64s_to_bytes(&[self.major, self.minor, self.patch])
+        algorithm::version_to_bytes(self.major, self.minor, self.patch)
     }
 
-    /// Get the bytes presentation of the version, as a `[u8; 24]`
+    /// Get the bytes presentation of the version, as a `[u8; 24]`, without the pre-release.
     pub fn to_bytes(&self) -> [u8; 24] {
         let mut bytes = [0u8; 24];
         bytes[0..8].copy_from_slice(&self.major.to_le_bytes());
@@ -274,21 +412,28 @@ impl FixedVersion {
         bytes
     }
 
-    /// If a [`FixedVersion`] compatible with another [`FixedVersion`]
+    /// If a [`FixedVersion`] compatible with another [`FixedVersion`], by the caret requirement `^expected_version`.
+    ///
+    /// Like cargo, a pre-release is only compatible with the pre-releases of the same `major.minor.patch`.
     pub fn is_compatible(&self, expected_version: &FixedVersion) -> bool {
         let compatible_cmp = semver::Comparator {
             op: semver::Op::Caret,
             major: expected_version.major,
             minor: Some(expected_version.minor),
             patch: Some(expected_version.patch),
-            pre: semver::Prerelease::EMPTY,
+            pre: semver::Version::from(*expected_version).pre,
         };
-        compatible_cmp.matches(&Version::new(self.major, self.minor, self.patch))
+        compatible_cmp.matches(&Version::from(*self))
     }
 
     /// If a [`FixedVersion`] matches a [`semver::Comparator`]?
     pub fn matches(&self, comparator: &semver::Comparator) -> bool {
-        comparator.matches(&Version::new(self.major, self.minor, self.patch))
+        comparator.matches(&Version::from(*self))
+    }
+
+    /// If a [`FixedVersion`] satisfies a [`semver::VersionReq`] like `>=1.2, <2`, with the pre-release rules of cargo.
+    pub fn satisfies(&self, requirement: &semver::VersionReq) -> bool {
+        requirement.matches(&Version::from(*self))
     }
 }
 
@@ -298,41 +443,71 @@ impl From<(u64, u64, u64)> for FixedVersion {
     }
 }
 
+/// The pre-release is dropped.
 impl From<FixedVersion> for (u64, u64, u64) {
     fn from(value: FixedVersion) -> Self {
         (value.major, value.minor, value.patch)
     }
 }
 
-impl From<Version> for FixedVersion {
-    fn from(value: Version) -> Self {
-        FixedVersion::new(value.major, value.minor, value.patch)
+/// The build metadata is dropped.
+///
+/// Fails if the pre-release is longer than [`version::MAX_PRERELEASE_LEN`].
+impl TryFrom<Version> for FixedVersion {
+    type Error = version::ParseVersionError;
+
+    fn try_from(value: Version) -> Result<Self, Self::Error> {
+        Ok(FixedVersion {
+            major: value.major,
+            minor: value.minor,
+            patch: value.patch,
+            pre: FixedPrerelease::parse(value.pre.as_str())?,
+        })
     }
 }
 
 impl From<FixedVersion> for Version {
     fn from(value: FixedVersion) -> Self {
-        Version::new(value.major, value.minor, value.patch)
+        Version {
+            major: value.major,
+            minor: value.minor,
+            patch: value.patch,
+            pre: semver::Prerelease::new(value.pre.as_str())
+                .expect("a `FixedPrerelease` is a valid semver pre-release"),
+            build: semver::BuildMetadata::EMPTY,
+        }
     }
 }
 
-/// Get the hash from a type name and version, use the same procedure as [`FixedId::from_type_name`], but better performance.
+/// Get the hash from a type name and version, use the same procedure as [`FixedId::from_type_name`],
+/// but accepts a type name which is not `'static`.
 ///
 /// It can't be used in const context.
-pub fn name_version_to_hash(name: &str, version: &FixedVersion) -> u64 {
-    let name_hash = rapidhash::rapidhash(name.as_bytes());
-    // let version_hash = rapidhash::rapidhash(&version.as_bytes());
-    let mut bytes = [0u8; 24];
-    bytes[0..8].copy_from_slice(&version.major.to_le_bytes());
-    bytes[8..16].copy_from_slice(&version.minor.to_le_bytes());
-    bytes[16..24].copy_from_slice(&version.patch.to_le_bytes());
-    rapid_mix(name_hash, rapidhash::rapidhash(&bytes))
+pub fn name_version_to_hash(name: &str, version: &FixedVersion) -> RawFixedId {
+    hash_name_version(
+        name.as_bytes(),
+        Some(version.to_bytes()),
+        version.pre.as_bytes(),
+    )
 }
 
-/// A trait for providing a const fixed string for the type name, used to avoid heap when need to format the type name.
+/// Select the smallest buffer size class that fits the type name, and concat the raw slice into it.
+macro_rules! sized_name_bytes {
+    ($slice:expr, $len:expr; $($size:literal),*) => {
+        $(
+            if $len <= $size {
+                &concat_str_slice::<$size>($slice)
+            } else
+        )* {
+            panic!("the type name is longer than `MAX_CONST_TYPENAME_LEN` (4096) bytes")
+        }
+    };
+}
+
+/// A trait for providing a const string for the type name, used to avoid heap when need to format the type name.
 ///
-/// Useful for types with generic parameters. the size of the type name is limited by `CONST_TYPENAME_LEN`, which can be
-/// configured by feature flags `len128`, `len64` and `len256`, the default is `len128`.
+/// Useful for types with generic parameters. The type name is concatenated from [`ConstTypeName::RAW_SLICE`] in const context,
+/// into a buffer sized by its length, so it's never truncated. Names longer than [`MAX_CONST_TYPENAME_LEN`] fail to compile.
 ///
 /// But note that implementing this trait for a lot of types will make your binary size larger,
 /// and slow down your compile time.
@@ -347,22 +522,40 @@ pub fn name_version_to_hash(name: &str, version: &FixedVersion) -> u64 {
 /// }
 ///
 /// impl<T: FixedTypeId> FixedTypeId for A<T> {
-///     const TYPE_NAME: &'static str = fstr_to_str(&Self::TYPE_NAME_FSTR);
+///     const TYPE_NAME: &'static str = <Self as ConstTypeName>::TYPE_NAME_STR;
 /// }
 ///
 /// impl<T: FixedTypeId> ConstTypeName for A<T> {
 ///     const RAW_SLICE: &[&str] = &["A", "<", T::TYPE_NAME, ">"];
 /// }
 ///
+/// # #[cfg(not(feature = "erase_name"))]
 /// assert_eq!(<A<u8> as FixedTypeId>::TYPE_NAME, "A<u8>");
 /// ```
 pub trait ConstTypeName {
-    /// A raw slice for the type name, used to create a fixed `fstr`.
+    /// A raw slice for the type name, concatenated to create the type name.
     ///
     /// It's the only const you should defined for your struct.
     const RAW_SLICE: &[&str];
-    /// A fixed string for the type name, used to avoid heap when need to format the type name.
-    const TYPE_NAME_FSTR: fixedstr_ext::fstr<CONST_TYPENAME_LEN> = slice_to_fstr(Self::RAW_SLICE);
+    /// The length of the type name, in bytes.
+    const TYPE_NAME_LEN: usize = str_slice_len(Self::RAW_SLICE);
+    /// The bytes of the type name, in the smallest power of two sized buffer which fits it.
+    #[doc(hidden)]
+    const TYPE_NAME_BYTES: &'static [u8] = sized_name_bytes!(
+        Self::RAW_SLICE, Self::TYPE_NAME_LEN;
+        16, 32, 64, 128, 256, 512, 1024, 2048, 4096
+    );
+    /// The type name, use it as [`FixedTypeId::TYPE_NAME`].
+    const TYPE_NAME_STR: &'static str = {
+        let (name, _) = Self::TYPE_NAME_BYTES.split_at(Self::TYPE_NAME_LEN);
+        // SAFETY: the bytes are concatenated from whole `&str`s.
+        unsafe { core::str::from_utf8_unchecked(name) }
+    };
+    /// A fixed string for the type name, its capacity is [`MAX_CONST_TYPENAME_LEN`].
+    #[deprecated(note = "use `TYPE_NAME_STR`, which is sized by the length of the type name")]
+    #[cfg(feature = "std")]
+    const TYPE_NAME_FSTR: fixedstr_ext::fstr<MAX_CONST_TYPENAME_LEN> =
+        slice_to_fstr(Self::RAW_SLICE);
 }
 
 /// A helper function to get the type name of a type.
@@ -384,15 +577,58 @@ pub fn type_version<T: ?Sized + FixedTypeId>() -> FixedVersion {
 }
 
 /// Helper function to convert a fixed string [`fixedstr_ext::fstr`] to a string.
+#[cfg(feature = "std")]
 pub const fn fstr_to_str<const N: usize>(fstr: &'static fixedstr_ext::fstr<N>) -> &'static str {
-    unsafe { core::str::from_raw_parts(fstr.to_ptr(), fstr.len()) }
+    unsafe {
+        core::str::from_utf8_unchecked(core::slice::from_raw_parts(fstr.to_ptr(), fstr.len()))
+    }
 }
 
 /// Helper function to convert a slice of string to a fixed string [`fixedstr_ext::fstr`].
+///
+/// Panics if the concatenated string is longer than `N`, instead of truncating it.
+#[cfg(feature = "std")]
 pub const fn slice_to_fstr<const N: usize>(slice: &[&str]) -> fixedstr_ext::fstr<N> {
+    if str_slice_len(slice) > N {
+        panic!("the concatenated string is longer than the fixed string");
+    }
     fixedstr_ext::fstr::<N>::const_create_from_str_slice(slice)
 }
 
+/// Helper function to get the total length of a slice of string, in bytes.
+pub const fn str_slice_len(slice: &[&str]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < slice.len() {
+        len += slice[i].len();
+        i += 1;
+    }
+    len
+}
+
+/// Helper function to concat a slice of string into a `[u8; N]`, the rest bytes are zero.
+///
+/// Panics if the concatenated string is longer than `N`.
+pub const fn concat_str_slice<const N: usize>(slice: &[&str]) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    let mut position = 0;
+    let mut i = 0;
+    while i < slice.len() {
+        let current = slice[i].as_bytes();
+        let mut j = 0;
+        while j < current.len() {
+            if position >= N {
+                panic!("the concatenated string is longer than the buffer");
+            }
+            bytes[position] = current[j];
+            position += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    bytes
+}
+
 #[cfg(feature = "specialization")]
 impl<T> FixedTypeId for T {
     default const TYPE_NAME: &'static str = "NOT_IMPLEMENTED";
@@ -402,6 +638,12 @@ impl<T> FixedTypeId for T {
 
     default const TYPE_VERSION: FixedVersion = FixedVersion::new(0, 0, 0);
 
+    default const TYPE_LAYOUT_HASH: u64 = Self::TYPE_ID.as_u64();
+
+    default fn layout_matches(layout_hash: u64) -> bool {
+        Self::TYPE_LAYOUT_HASH == layout_hash
+    }
+
     default fn ty_name(&self) -> &'static str {
         Self::TYPE_NAME
     }
@@ -417,7 +659,8 @@ impl<T> FixedTypeId for T {
 
 #[cfg(test)]
 mod tests {
-    use crate::name_version_to_hash;
+    use crate::algorithm::FixedIdAlgorithm;
+    use crate::{name_version_to_hash, IdAlgorithm};
 
     use super::prelude::*;
 
@@ -435,11 +678,14 @@ mod tests {
           #[equal_to(A1)]
           A2;
         }
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(
             <A1 as FixedTypeId>::TYPE_ID,
             FixedId::from_type_name(<A1 as FixedTypeId>::TYPE_NAME, None)
         );
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(<A1 as FixedTypeId>::TYPE_NAME, "A1");
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(<A2 as FixedTypeId>::TYPE_NAME, "A2");
         assert_eq!(<A1 as FixedTypeId>::TYPE_ID, <A2 as FixedTypeId>::TYPE_ID);
         assert_eq!(
@@ -461,8 +707,11 @@ mod tests {
           A<u8>;
           A<u16>;
         }
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(<A<u8> as FixedTypeId>::TYPE_NAME, "A<u8>");
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(<A<u16> as FixedTypeId>::TYPE_NAME, "A<u16>");
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(
             <A<u8> as FixedTypeId>::TYPE_ID,
             FixedId::from_type_name(
@@ -470,6 +719,7 @@ mod tests {
                 Some(FixedVersion::new(0, 0, 0))
             )
         );
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(
             <A<u16> as FixedTypeId>::TYPE_ID,
             FixedId::from_type_name(
@@ -495,8 +745,7 @@ mod tests {
     fn macro_manual_diff() {
         // with versin hash, default implementation
         mod a {
-            use super::fixed_type_id;
-            use super::{FixedId, FixedTypeId, FixedVersion};
+            use crate::prelude::*;
 
             pub struct A;
             fixed_type_id! {
@@ -525,7 +774,9 @@ mod tests {
             <b::A as FixedTypeId>::TYPE_VERSION,
             <a::A as FixedTypeId>::TYPE_VERSION
         );
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(<a::A as FixedTypeId>::TYPE_NAME, "A");
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(
             <b::A as FixedTypeId>::TYPE_NAME,
             <a::A as FixedTypeId>::TYPE_NAME
@@ -563,6 +814,7 @@ mod tests {
         };
         impl DefTrait for u8 {}
 
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(
             <GenericType<u8> as FixedTypeId>::TYPE_NAME,
             "tests::generic_auto::GenericType<u8>"
@@ -588,10 +840,12 @@ mod tests {
         fixed_type_id! {
             #[version((0,1,0))]
             #[omit_version_hash]
+            #[phantom(T)]
             tests::generic_auto::GenericType<T:, U:FixedTypeId + DefTrait>;
         };
         impl DefTrait for u8 {}
 
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(
             <GenericType<u8, u8> as FixedTypeId>::TYPE_NAME,
             "tests::generic_auto::GenericType<u8>"
@@ -616,6 +870,7 @@ mod tests {
             tests::generic_auto::GenericType2<T:FixedTypeId + DefTrait, U:FixedTypeId + DefTrait>;
         };
 
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(
             <GenericType2<u8, u8> as FixedTypeId>::TYPE_NAME,
             "tests::generic_auto::GenericType2<u8,u8>"
@@ -652,6 +907,7 @@ mod tests {
         };
         impl DefTrait for u8 {}
 
+        #[cfg(not(feature = "erase_name"))]
         assert_eq!(
             <GenericType<u8> as FixedTypeId>::TYPE_NAME,
             "tests::generic_auto::GenericType<u8>"
@@ -665,4 +921,582 @@ mod tests {
             <EqualType<u8> as FixedTypeId>::TYPE_ID
         )
     }
+
+    #[test]
+    fn derive_same_as_macro() {
+        mod a {
+            use crate::prelude::*;
+
+            pub struct A;
+            fixed_type_id! {
+                #[version((1,2,0))]
+                my::A;
+            }
+        }
+        mod b {
+            use super::*;
+
+            #[derive(FixedTypeId)]
+            #[fixed_type_id(name = "my::A", version = "1.2.0")]
+            pub struct A;
+        }
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<b::A as FixedTypeId>::TYPE_NAME, "my::A");
+        assert_eq!(
+            <b::A as FixedTypeId>::TYPE_VERSION,
+            <a::A as FixedTypeId>::TYPE_VERSION
+        );
+        assert_eq!(
+            <b::A as FixedTypeId>::TYPE_ID,
+            <a::A as FixedTypeId>::TYPE_ID
+        );
+    }
+
+    #[test]
+    fn derive_struct_enum_union() {
+        #[derive(FixedTypeId)]
+        pub struct S {
+            pub _x: u8,
+        }
+        #[derive(FixedTypeId)]
+        #[fixed_type_id(name = "E", omit_version_hash)]
+        pub enum E {
+            _A,
+        }
+        #[derive(FixedTypeId)]
+        #[fixed_type_id(name = "U", equal_to = E)]
+        pub union U {
+            pub _x: u8,
+        }
+
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<S as FixedTypeId>::TYPE_NAME, "fixed_type_id::tests::S");
+        assert_eq!(
+            <S as FixedTypeId>::TYPE_ID,
+            FixedId::from_type_name("fixed_type_id::tests::S", Some(FixedVersion::new(0, 0, 0)))
+        );
+        assert_eq!(
+            <E as FixedTypeId>::TYPE_ID,
+            FixedId::from_type_name("E", None)
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<U as FixedTypeId>::TYPE_NAME, "U");
+        assert_eq!(<U as FixedTypeId>::TYPE_ID, <E as FixedTypeId>::TYPE_ID);
+    }
+
+    #[cfg(feature = "erase_name")]
+    #[test]
+    fn derive_erase_name() {
+        use crate::algorithm::rapidhash::rapidhash;
+
+        #[derive(FixedTypeId)]
+        pub struct S;
+        #[derive(FixedTypeId)]
+        #[fixed_type_id(name = "my::Named")]
+        pub struct Named;
+
+        let erased = |name: &str| format!("0x{:x}", rapidhash(name.as_bytes()));
+        assert_eq!(
+            <S as FixedTypeId>::TYPE_NAME,
+            erased("fixed_type_id::tests::S")
+        );
+        // the id is still hashed from the original name
+        assert_eq!(
+            <S as FixedTypeId>::TYPE_ID,
+            FixedId::from_type_name("fixed_type_id::tests::S", Some(FixedVersion::new(0, 0, 0)))
+        );
+        assert_eq!(<Named as FixedTypeId>::TYPE_NAME, erased("my::Named"));
+    }
+
+    #[test]
+    fn version_forms() {
+        mod versions {
+            use crate::prelude::*;
+            pub struct Tuple;
+            pub struct Str;
+            pub struct Crate;
+            pub struct Pre;
+            fixed_type_id! {
+                #[version((1,2,3))]
+                versions::Tuple
+            }
+            fixed_type_id! {
+                #[version("1.2.3+build.5")]
+                versions::Str
+            }
+            fixed_type_id! {
+                #[version(crate)]
+                versions::Crate
+            }
+            fixed_type_id! {
+                #[version("2.0.0-beta.3+build.5")]
+                versions::Pre
+            }
+            #[derive(FixedTypeId)]
+            #[fixed_type_id(name = "versions::Derived", version = crate)]
+            pub struct Derived;
+            #[derive(FixedTypeId)]
+            #[fixed_type_id(name = "versions::DerivedPre", version = "1.0.0-rc.1")]
+            pub struct DerivedPre;
+        }
+        let crate_version: FixedVersion = semver::Version::parse(env!("CARGO_PKG_VERSION"))
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            <versions::Tuple as FixedTypeId>::TYPE_VERSION,
+            FixedVersion::new(1, 2, 3)
+        );
+        assert_eq!(
+            <versions::Str as FixedTypeId>::TYPE_VERSION,
+            FixedVersion::new(1, 2, 3)
+        );
+        assert_eq!(
+            <versions::Crate as FixedTypeId>::TYPE_VERSION,
+            crate_version
+        );
+        assert_eq!(
+            <versions::Crate as FixedTypeId>::TYPE_ID,
+            FixedId::from_type_name("versions::Crate", Some(crate_version))
+        );
+        assert_eq!(
+            <versions::Derived as FixedTypeId>::TYPE_VERSION,
+            crate_version
+        );
+        let pre = FixedVersion::new_pre(2, 0, 0, "beta.3");
+        assert_eq!(<versions::Pre as FixedTypeId>::TYPE_VERSION, pre);
+        // the id hashed by the macro is the same as the one hashed in const context
+        assert_eq!(
+            <versions::Pre as FixedTypeId>::TYPE_ID,
+            FixedId::from_type_name("versions::Pre", Some(pre))
+        );
+        assert_ne!(
+            <versions::Pre as FixedTypeId>::TYPE_ID,
+            FixedId::from_type_name("versions::Pre", Some(FixedVersion::new(2, 0, 0)))
+        );
+        assert_eq!(
+            <versions::DerivedPre as FixedTypeId>::TYPE_VERSION,
+            FixedVersion::new_pre(1, 0, 0, "rc.1")
+        );
+    }
+
+    #[test]
+    fn derive_generic() {
+        #[derive(FixedTypeId)]
+        #[fixed_type_id(name = "tests::generic_derive::GenericType", version = "0.1.0")]
+        pub struct GenericType<'a, T, U: Copy> {
+            pub _t: &'a T,
+            pub _u: U,
+        }
+
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <GenericType<'static, u8, u16> as FixedTypeId>::TYPE_NAME,
+            "tests::generic_derive::GenericType<u8,u16>"
+        );
+        assert_eq!(
+            <GenericType<'static, u8, u16> as FixedTypeId>::TYPE_VERSION,
+            FixedVersion::new(0, 1, 0)
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <GenericType<'static, u8, u16> as FixedTypeId>::TYPE_ID,
+            FixedId::from_type_name(
+                "tests::generic_derive::GenericType<u8,u16>",
+                Some(FixedVersion::new(0, 1, 0))
+            )
+        );
+        assert_ne!(
+            <GenericType<'static, u8, u16> as FixedTypeId>::TYPE_ID,
+            <GenericType<'static, u16, u8> as FixedTypeId>::TYPE_ID
+        );
+    }
+
+    #[test]
+    fn derive_layout_hash() {
+        macro_rules! config {
+            ($module:ident { $($field:ident: $ty:ty),* }) => {
+                #[allow(dead_code)]
+                mod $module {
+                    use crate::prelude::*;
+
+                    #[derive(FixedTypeId)]
+                    #[fixed_type_id(name = "Config", layout)]
+                    pub struct Config {
+                        $(pub $field: $ty),*
+                    }
+                }
+            };
+        }
+        config!(same {
+            width: u32,
+            height: u32
+        });
+        config!(base {
+            width: u32,
+            height: u32
+        });
+        config!(added {
+            width: u32,
+            height: u32,
+            depth: u32
+        });
+        config!(renamed {
+            width: u32,
+            length: u32
+        });
+        config!(reordered {
+            height: u32,
+            width: u32
+        });
+        config!(retyped {
+            width: u32,
+            height: u64
+        });
+        fn layout<T: FixedTypeId>() -> u64 {
+            T::TYPE_LAYOUT_HASH
+        }
+        let base = layout::<base::Config>();
+        assert_eq!(layout::<same::Config>(), base);
+        assert!(<same::Config as FixedTypeId>::layout_matches(base));
+        for changed in [
+            layout::<added::Config>(),
+            layout::<renamed::Config>(),
+            layout::<reordered::Config>(),
+            layout::<retyped::Config>(),
+        ] {
+            assert_ne!(changed, base);
+        }
+        // the id doesn't change without a version bump
+        assert_eq!(
+            <added::Config as FixedTypeId>::TYPE_ID,
+            <base::Config as FixedTypeId>::TYPE_ID
+        );
+
+        #[allow(dead_code)]
+        mod shapes {
+            use crate::prelude::*;
+
+            #[derive(FixedTypeId)]
+            #[fixed_type_id(layout)]
+            pub struct Inner {
+                pub x: u32,
+            }
+
+            #[derive(FixedTypeId)]
+            #[fixed_type_id(layout)]
+            pub struct Outer<T> {
+                pub inner: Inner,
+                pub t: T,
+            }
+
+            #[derive(FixedTypeId)]
+            #[fixed_type_id(layout)]
+            pub enum E {
+                A(u8),
+                B { x: u16 },
+            }
+
+            #[derive(FixedTypeId)]
+            #[fixed_type_id(layout)]
+            pub enum F {
+                A(u8),
+                B { y: u16 },
+            }
+
+            #[derive(FixedTypeId)]
+            pub struct Plain(pub u8);
+        }
+        use crate::layout::LayoutHasher;
+        use shapes::*;
+        assert_eq!(
+            layout::<Outer<u8>>(),
+            LayoutHasher::of::<Outer<u8>>()
+                .field::<Inner>("inner")
+                .field::<u8>("t")
+                .finish()
+        );
+        assert_ne!(layout::<Outer<u8>>(), layout::<Outer<i8>>());
+        assert_ne!(layout::<E>(), layout::<F>());
+        // without `layout`, it's the id
+        assert_eq!(layout::<Plain>(), <Plain as FixedTypeId>::TYPE_ID.as_u64());
+        assert_eq!(layout::<u8>(), <u8 as FixedTypeId>::TYPE_ID.as_u64());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn long_type_name_not_truncated() {
+        use std::collections::HashMap;
+
+        type Inner = HashMap<String, Vec<(u32, Box<Option<String>>)>>;
+        type Long<T> = Vec<Vec<Vec<HashMap<Inner, Vec<(Inner, T)>>>>>;
+
+        let inner = "std::collections::HashMap<alloc::string::String,alloc::vec::Vec<(u32,alloc::boxed::Box<core::option::Option<alloc::string::String>>)>>";
+        let long = |last: &str| {
+            format!(
+                "alloc::vec::Vec<alloc::vec::Vec<alloc::vec::Vec<std::collections::HashMap<{inner},alloc::vec::Vec<({inner},{last})>>>>>"
+            )
+        };
+        assert!(long("u8").len() > 256);
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<Long<u8> as FixedTypeId>::TYPE_NAME, long("u8"));
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<Long<u8> as ConstTypeName>::TYPE_NAME_LEN, long("u8").len());
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<Long<u16> as FixedTypeId>::TYPE_NAME, long("u16"));
+        // names which only differ after the old length limit have different ids
+        assert_ne!(
+            <Long<u8> as FixedTypeId>::TYPE_ID,
+            <Long<u16> as FixedTypeId>::TYPE_ID
+        );
+    }
+
+    #[test]
+    fn derive_const_generic() {
+        mod m {
+            use crate::prelude::*;
+
+            #[derive(FixedTypeId)]
+            #[fixed_type_id(name = "tests::Buf", omit_version_hash)]
+            pub struct Buf<const N: usize, T, const SIGNED: bool = false> {
+                pub _data: [T; N],
+            }
+
+            #[derive(FixedTypeId)]
+            #[fixed_type_id(name = "tests::Offset")]
+            pub struct Offset<const O: i32>;
+        }
+        use m::*;
+
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <Buf<33, u8> as FixedTypeId>::TYPE_NAME,
+            "tests::Buf<33,u8,false>"
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <Buf<100, u8, true> as FixedTypeId>::TYPE_NAME,
+            "tests::Buf<100,u8,true>"
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <Buf<100, u8, true> as FixedTypeId>::TYPE_ID,
+            FixedId::from_type_name("tests::Buf<100,u8,true>", None)
+        );
+        assert_ne!(
+            <Buf<33, u8> as FixedTypeId>::TYPE_ID,
+            <Buf<100, u8> as FixedTypeId>::TYPE_ID
+        );
+        assert_eq!(<Offset<-7> as FixedTypeId>::TYPE_NAME, "tests::Offset<-7>");
+    }
+
+    #[test]
+    fn macro_const_generic() {
+        mod m {
+            use crate::prelude::*;
+
+            pub struct Buf<const N: usize, T> {
+                pub _data: [T; N],
+            }
+            pub struct View<'a, T, const SIGNED: bool, const C: char> {
+                pub _data: &'a [T],
+            }
+            fixed_type_id! {
+                #[version((0,1,0))]
+                tests::Buf<const N: usize, T: FixedTypeId>;
+                tests::View<'a, T: FixedTypeId, const SIGNED: bool, const C: char>;
+            }
+        }
+        use m::*;
+
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<Buf<16, u8> as FixedTypeId>::TYPE_NAME, "tests::Buf<16,u8>");
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <Buf<16, u8> as FixedTypeId>::TYPE_ID,
+            FixedId::from_type_name("tests::Buf<16,u8>", Some(FixedVersion::new(0, 1, 0)))
+        );
+        assert_ne!(
+            <Buf<16, u8> as FixedTypeId>::TYPE_ID,
+            <Buf<17, u8> as FixedTypeId>::TYPE_ID
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <View<'static, u16, true, 'x'> as FixedTypeId>::TYPE_NAME,
+            "tests::View<u16,true,'x'>"
+        );
+        assert_ne!(
+            <View<'static, u16, true, 'x'> as FixedTypeId>::TYPE_ID,
+            <View<'static, u16, false, 'x'> as FixedTypeId>::TYPE_ID
+        );
+    }
+
+    #[test]
+    fn macro_phantom_param() {
+        mod m {
+            use crate::prelude::*;
+
+            pub struct Tagged<T, U, S> {
+                pub _data: (T, U, S),
+            }
+            fixed_type_id! {
+                #[phantom(U = "_", S)]
+                tests::Tagged<T: FixedTypeId, U:, S:>;
+            }
+        }
+        use m::*;
+
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <Tagged<u8, String, ()> as FixedTypeId>::TYPE_NAME,
+            "tests::Tagged<u8,_>"
+        );
+        assert_eq!(
+            <Tagged<u8, String, ()> as FixedTypeId>::TYPE_ID,
+            <Tagged<u8, u32, u64> as FixedTypeId>::TYPE_ID
+        );
+        assert_ne!(
+            <Tagged<u8, String, ()> as FixedTypeId>::TYPE_ID,
+            <Tagged<u16, String, ()> as FixedTypeId>::TYPE_ID
+        );
+    }
+
+    #[test]
+    fn macro_type_grammar() {
+        mod m {
+            use crate::prelude::*;
+
+            pub struct A;
+            pub trait Handler {}
+
+            fixed_type_id! {
+                A;
+                dyn tests::Handler + Sync + Send;
+                dyn tests::Handler + Send + 'static;
+                dyn Iterator<Item = A>;
+            }
+        }
+        use m::*;
+
+        // the auto traits are ordered, and `'static` is omitted
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <dyn Handler + Send + Sync as FixedTypeId>::TYPE_NAME,
+            "dyn tests::Handler + Send + Sync"
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <dyn Handler + Send as FixedTypeId>::TYPE_NAME,
+            "dyn tests::Handler + Send"
+        );
+        assert_ne!(
+            <dyn Handler + Send as FixedTypeId>::TYPE_ID,
+            <dyn Handler + Send + Sync as FixedTypeId>::TYPE_ID
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <Box<dyn Handler + Send> as FixedTypeId>::TYPE_NAME,
+            "alloc::boxed::Box<dyn tests::Handler + Send>"
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <dyn Iterator<Item = A> as FixedTypeId>::TYPE_NAME,
+            "dyn Iterator<Item=A>"
+        );
+    }
+
+    // with specialization every type implements `FixedTypeId`, so the built-in impls cover these types
+    #[cfg(not(feature = "specialization"))]
+    #[test]
+    fn macro_other_types() {
+        mod m {
+            use crate::prelude::*;
+
+            // they don't implement `FixedTypeId`, so the built-in impls don't apply to them
+            pub struct Opaque;
+            pub struct Elem;
+
+            fixed_type_id! {
+                [tests::Opaque];
+                *const tests::Opaque;
+                *mut [tests::Elem];
+                fn(tests::Opaque, &'static mut tests::Opaque) -> tests::Elem;
+                unsafe extern "C" fn(*mut tests::Opaque);
+                &'static mut [tests::Elem];
+                &'static mut tests::Elem;
+            }
+        }
+        use m::*;
+
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<[Opaque] as FixedTypeId>::TYPE_NAME, "[tests::Opaque]");
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <*const Opaque as FixedTypeId>::TYPE_NAME,
+            "*const tests::Opaque"
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <*mut [Elem] as FixedTypeId>::TYPE_NAME,
+            "*mut [tests::Elem]"
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <fn(Opaque, &'static mut Opaque) -> Elem as FixedTypeId>::TYPE_NAME,
+            "fn(tests::Opaque,&muttests::Opaque) -> tests::Elem"
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <unsafe extern "C" fn(*mut Opaque) as FixedTypeId>::TYPE_NAME,
+            "unsafe extern \"C\" fn(*mut tests::Opaque) -> ()"
+        );
+        // references are named like the built-in `&mut [T]` and `&mut T`, without lifetimes
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(
+            <&mut [Elem] as FixedTypeId>::TYPE_NAME,
+            "&mut [tests::Elem]"
+        );
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<&mut Elem as FixedTypeId>::TYPE_NAME, "&muttests::Elem");
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<&mut [u8] as FixedTypeId>::TYPE_NAME, "&mut [u8]");
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(<&mut u8 as FixedTypeId>::TYPE_NAME, "&mutu8");
+        assert_ne!(
+            <[Opaque] as FixedTypeId>::TYPE_ID,
+            <*const Opaque as FixedTypeId>::TYPE_ID
+        );
+    }
+
+    #[test]
+    fn id_low_bits_stable() {
+        mod m {
+            use crate::prelude::*;
+
+            pub struct A;
+
+            fixed_type_id! {
+                #[version((0,1,0))]
+                tests::id128::A;
+            }
+        }
+        use m::*;
+
+        let version = FixedVersion::new(0, 1, 0);
+        let id = <A as FixedTypeId>::TYPE_ID;
+        assert_eq!(id.0, name_version_to_hash("tests::id128::A", &version));
+        assert_eq!(
+            id.as_u64(),
+            IdAlgorithm::combine(
+                IdAlgorithm::hash_seeded(b"tests::id128::A", IdAlgorithm::SEED),
+                IdAlgorithm::hash_seeded(&version.to_bytes(), IdAlgorithm::SEED),
+                IdAlgorithm::SEED
+            )
+        );
+        assert_eq!(FixedId::from_u64(id.as_u64()).as_u64(), id.as_u64());
+        #[cfg(feature = "id128")]
+        assert_ne!(id.as_u128() >> 64, 0);
+        #[cfg(not(feature = "id128"))]
+        assert_eq!(id.as_u128(), id.as_u64() as u128);
+    }
 }
@@ -0,0 +1,555 @@
+//! A manifest of `(TYPE_NAME, TYPE_VERSION, TYPE_ID)`, to pin the ids of types between releases.
+//!
+//! A manifest is a TOML file with one `[[type]]` table per type, sorted by name, so it can be checked in
+//! and reviewed like any other source file:
+//!
+//! ```toml
+//! [[type]]
+//! name = "u8"
+//! version = "0.0.0"
+//! id = "0x..."
+//! ```
+//!
+//! The lockfiles written by `#[store_in_file(...)]` and `#[lock_in_file(...)]` of [`fixed_type_id!`](crate::fixed_type_id!)
+//! use the same format, with an additional `location` key.
+//!
+//! [`verify_stability`] compares the ids of the given types with a checked-in manifest, and fails on any drift,
+//! this crate uses it to pin the ids of all its built-in implementations.
+//!
+//! ```rust
+//! use fixed_type_id::manifest::{Manifest, ManifestEntry};
+//! use fixed_type_id::prelude::*;
+//!
+//! let manifest = Manifest::new(fixed_type_id::manifest_entries![u8, Vec<u8>]);
+//! let parsed: Manifest = manifest.to_toml().parse().unwrap();
+//! assert_eq!(parsed, manifest);
+//! # #[cfg(not(feature = "erase_name"))]
+//! assert_eq!(parsed.get("u8"), Some(&ManifestEntry::of::<u8>()));
+//! assert!(parsed.diff(&manifest).is_empty());
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::{env, fmt, fs, io};
+
+use crate::{FixedId, FixedTypeId, FixedVersion, RawFixedId};
+
+/// The environment variable which makes [`verify_stability`] write the manifest instead of checking it.
+pub const BLESS_ENV: &str = "FIXED_TYPE_ID_BLESS";
+
+/// The name, version and id of a type in a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ManifestEntry {
+    /// The [`FixedTypeId::TYPE_NAME`] of the type.
+    pub name: String,
+    /// The [`FixedTypeId::TYPE_VERSION`] of the type.
+    pub version: FixedVersion,
+    /// The [`FixedTypeId::TYPE_ID`] of the type.
+    pub id: FixedId,
+    /// Where the type is declared, like `src/lib.rs:12`, only recorded in lockfiles.
+    pub location: Option<String>,
+}
+
+impl ManifestEntry {
+    /// Create the entry of a type.
+    pub fn of<T: ?Sized + FixedTypeId>() -> Self {
+        ManifestEntry {
+            name: T::TYPE_NAME.to_string(),
+            version: T::TYPE_VERSION,
+            id: T::TYPE_ID,
+            location: None,
+        }
+    }
+
+    /// Returns `true` if the two entries have the same name, version and id, the location is ignored.
+    pub fn same_id(&self, other: &ManifestEntry) -> bool {
+        self.name == other.name && self.version == other.version && self.id == other.id
+    }
+}
+
+impl fmt::Display for ManifestEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.name,
+            format_version(&self.version),
+            format_id(self.id)
+        )
+    }
+}
+
+/// Create a `Vec` of [`ManifestEntry`] of the given types.
+///
+/// ```rust,ignore
+/// let entries = fixed_type_id::manifest_entries![u8, Vec<u8>, MyType];
+/// ```
+#[macro_export]
+macro_rules! manifest_entries {
+    ($($ty:ty),* $(,)?) => {
+        vec![$($crate::manifest::ManifestEntry::of::<$ty>()),*]
+    };
+}
+
+/// A set of [`ManifestEntry`], keyed and sorted by type name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    entries: BTreeMap<String, ManifestEntry>,
+}
+
+impl Manifest {
+    /// Create a manifest from entries, a later entry replaces an earlier one with the same name.
+    pub fn new(entries: impl IntoIterator<Item = ManifestEntry>) -> Self {
+        Manifest {
+            entries: entries
+                .into_iter()
+                .map(|entry| (entry.name.clone(), entry))
+                .collect(),
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there is no entry.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the entry of a type name.
+    pub fn get(&self, name: &str) -> Option<&ManifestEntry> {
+        self.entries.get(name)
+    }
+
+    /// Find the entries with the given id, more than one means a collision.
+    pub fn find_id(&self, id: FixedId) -> impl Iterator<Item = &ManifestEntry> {
+        self.entries.values().filter(move |entry| entry.id == id)
+    }
+
+    /// Iterate over the entries, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = &ManifestEntry> {
+        self.entries.values()
+    }
+
+    /// Insert an entry, returns the replaced entry with the same name, if any.
+    pub fn insert(&mut self, entry: ManifestEntry) -> Option<ManifestEntry> {
+        self.entries.insert(entry.name.clone(), entry)
+    }
+
+    /// Compare with a newer manifest, entries which only differ in location are the same.
+    pub fn diff(&self, new: &Manifest) -> ManifestDiff {
+        let mut diff = ManifestDiff::default();
+        for old in self.iter() {
+            match new.get(&old.name) {
+                None => diff.removed.push(old.clone()),
+                Some(new) if !new.same_id(old) => diff.changed.push((old.clone(), new.clone())),
+                Some(_) => {}
+            }
+        }
+        diff.added = new
+            .iter()
+            .filter(|entry| self.get(&entry.name).is_none())
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// Render the manifest as TOML, the output only depends on the entries.
+    pub fn to_toml(&self) -> String {
+        let mut toml = String::from(
+            "# Generated by fixed-type-id, one `[[type]]` per type, sorted by name.\n",
+        );
+        for entry in self.iter() {
+            toml.push_str("\n[[type]]\n");
+            toml.push_str(&format!("name = {}\n", quote(&entry.name)));
+            toml.push_str(&format!(
+                "version = \"{}\"\n",
+                format_version(&entry.version)
+            ));
+            toml.push_str(&format!("id = \"{}\"\n", format_id(entry.id)));
+            if let Some(location) = &entry.location {
+                toml.push_str(&format!("location = {}\n", quote(location)));
+            }
+        }
+        toml
+    }
+}
+
+impl FromIterator<ManifestEntry> for Manifest {
+    fn from_iter<I: IntoIterator<Item = ManifestEntry>>(iter: I) -> Self {
+        Manifest::new(iter)
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = ManifestError;
+
+    /// Parse a manifest rendered by [`Manifest::to_toml`].
+    ///
+    /// Only the subset of TOML used by manifests is supported: comments, `[[type]]` headers and
+    /// `key = "string"` pairs, unknown keys are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut manifest = Manifest::default();
+        let mut current: Option<PartialEntry> = None;
+        for (index, line) in s.lines().enumerate() {
+            let line_no = index + 1;
+            let error = |message: String| ManifestError {
+                line: line_no,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line == "[[type]]" {
+                if let Some(entry) = current.take() {
+                    manifest.push(entry)?;
+                }
+                current = Some(PartialEntry::new(line_no));
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(error(format!(
+                    "expected `[[type]]` or `key = \"value\"`, found `{line}`"
+                )));
+            };
+            let Some(entry) = current.as_mut() else {
+                return Err(error("a key outside of a `[[type]]` table".to_string()));
+            };
+            let value = unquote(value.trim()).map_err(error)?;
+            match key.trim() {
+                "name" => entry.name = Some(value),
+                "version" => entry.version = Some(parse_version(&value).map_err(error)?),
+                "id" => entry.id = Some(parse_id(&value).map_err(error)?),
+                "location" => entry.location = Some(value),
+                _ => {}
+            }
+        }
+        if let Some(entry) = current {
+            manifest.push(entry)?;
+        }
+        Ok(manifest)
+    }
+}
+
+impl Manifest {
+    fn push(&mut self, entry: PartialEntry) -> Result<(), ManifestError> {
+        let line = entry.line;
+        let entry = entry.finish()?;
+        if self.entries.contains_key(&entry.name) {
+            return Err(ManifestError {
+                line,
+                message: format!("duplicate type name `{}`", entry.name),
+            });
+        }
+        self.insert(entry);
+        Ok(())
+    }
+}
+
+/// An entry being parsed, `line` is the line of its `[[type]]` header.
+struct PartialEntry {
+    line: usize,
+    name: Option<String>,
+    version: Option<FixedVersion>,
+    id: Option<FixedId>,
+    location: Option<String>,
+}
+
+impl PartialEntry {
+    fn new(line: usize) -> Self {
+        PartialEntry {
+            line,
+            name: None,
+            version: None,
+            id: None,
+            location: None,
+        }
+    }
+
+    fn finish(self) -> Result<ManifestEntry, ManifestError> {
+        let missing = |key: &str| ManifestError {
+            line: self.line,
+            message: format!("missing key `{key}`"),
+        };
+        Ok(ManifestEntry {
+            name: self.name.ok_or_else(|| missing("name"))?,
+            version: self.version.ok_or_else(|| missing("version"))?,
+            id: self.id.ok_or_else(|| missing("id"))?,
+            location: self.location,
+        })
+    }
+}
+
+/// An error when parsing a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestError {
+    /// The line where the error occurs, starts from 1.
+    pub line: usize,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid manifest at line {}: {}",
+            self.line, self.message
+        )
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+/// The difference between two [`Manifest`]s, see [`Manifest::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+    /// The entries only in the new manifest.
+    pub added: Vec<ManifestEntry>,
+    /// The entries only in the old manifest.
+    pub removed: Vec<ManifestEntry>,
+    /// The entries whose version or id changed, as `(old, new)`.
+    pub changed: Vec<(ManifestEntry, ManifestEntry)>,
+}
+
+impl ManifestDiff {
+    /// Returns `true` if the two manifests are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for ManifestDiff {
+    /// One line per entry, prefixed with `+` for added, `-` for removed and `~` for changed entries.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.added {
+            writeln!(f, "+ {entry}")?;
+        }
+        for entry in &self.removed {
+            writeln!(f, "- {entry}")?;
+        }
+        for (old, new) in &self.changed {
+            writeln!(
+                f,
+                "~ {}: {} {} -> {} {}",
+                old.name,
+                format_version(&old.version),
+                format_id(old.id),
+                format_version(&new.version),
+                format_id(new.id)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The error returned by [`verify_stability`].
+#[derive(Debug)]
+pub enum StabilityError {
+    /// The manifest can't be read or written.
+    Io(io::Error),
+    /// The manifest can't be parsed.
+    Manifest(ManifestError),
+    /// The current entries differ from the manifest.
+    Drift(ManifestDiff),
+}
+
+impl fmt::Display for StabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StabilityError::Io(error) => write!(f, "can't access the manifest: {error}"),
+            StabilityError::Manifest(error) => error.fmt(f),
+            StabilityError::Drift(diff) => write!(
+                f,
+                "the type ids drifted from the manifest:\n{diff}\
+                 if the change is intended, rerun with `{BLESS_ENV}=1` to update the manifest"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StabilityError {}
+
+impl From<io::Error> for StabilityError {
+    fn from(error: io::Error) -> Self {
+        StabilityError::Io(error)
+    }
+}
+
+impl From<ManifestError> for StabilityError {
+    fn from(error: ManifestError) -> Self {
+        StabilityError::Manifest(error)
+    }
+}
+
+/// Check that the names, versions and ids of `current` are exactly the ones in the manifest file at `path`.
+///
+/// Any added, removed or changed entry is reported as [`StabilityError::Drift`].
+/// When the environment variable [`BLESS_ENV`] is set, the manifest is written from `current` instead.
+///
+/// Call it in a test of your crate to pin the ids of your types:
+///
+/// ```rust,ignore
+/// #[test]
+/// fn ids_are_stable() {
+///     let entries = fixed_type_id::manifest_entries![MyType, MyOtherType<u8>];
+///     let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/ids.toml");
+///     fixed_type_id::manifest::verify_stability(path, &entries).unwrap();
+/// }
+/// ```
+pub fn verify_stability(
+    path: impl AsRef<Path>,
+    current: &[ManifestEntry],
+) -> Result<(), StabilityError> {
+    let current = Manifest::new(current.iter().cloned());
+    if env::var_os(BLESS_ENV).is_some() {
+        fs::write(path, current.to_toml())?;
+        return Ok(());
+    }
+    let golden: Manifest = fs::read_to_string(path)?.parse()?;
+    let diff = golden.diff(&current);
+    if diff.is_empty() {
+        Ok(())
+    } else {
+        Err(StabilityError::Drift(diff))
+    }
+}
+
+/// Format the version as `major.minor.patch[-pre]`, as in manifests.
+pub fn format_version(version: &FixedVersion) -> String {
+    version.to_string()
+}
+
+/// Format the id as hex, with all the digits of [`RawFixedId`], as in manifests.
+pub fn format_id(id: FixedId) -> String {
+    format!(
+        "{:#0width$x}",
+        id.0,
+        width = 2 + 2 * size_of::<RawFixedId>()
+    )
+}
+
+/// Parse a version formatted by [`format_version`].
+pub fn parse_version(s: &str) -> Result<FixedVersion, String> {
+    s.parse().map_err(|error| format!("`{s}`: {error}"))
+}
+
+/// Parse an id formatted by [`format_id`], leading zeros can be omitted.
+pub fn parse_id(s: &str) -> Result<FixedId, String> {
+    s.strip_prefix("0x")
+        .and_then(|hex| RawFixedId::from_str_radix(hex, 16).ok())
+        .map(FixedId)
+        .ok_or_else(|| format!("invalid id `{s}`, expected a hex integer like `0x1f`"))
+}
+
+/// Quote a string as a TOML basic string.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Unquote a TOML basic string produced by [`quote`].
+fn unquote(s: &str) -> Result<String, String> {
+    let inner = s
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a quoted string, found `{s}`"))?;
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unquoted.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('"') => unquoted.push('"'),
+            Some('\\') => unquoted.push('\\'),
+            Some('n') => unquoted.push('\n'),
+            Some('t') => unquoted.push('\t'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let c = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("invalid escape `\\u{hex}`"))?;
+                unquoted.push(c);
+            }
+            other => return Err(format!("unsupported escape `\\{}`", other.unwrap_or(' '))),
+        }
+    }
+    Ok(unquoted)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::manifest::{Manifest, ManifestEntry};
+    use crate::prelude::*;
+
+    #[test]
+    fn round_trip() {
+        let mut manifest = Manifest::new(crate::manifest_entries![u8, Vec<u8>, &str, (u8, u16)]);
+        manifest.insert(ManifestEntry {
+            name: "quote\"and\\slash\u{1}".to_string(),
+            version: FixedVersion::new_pre(1, 2, 3, "rc.1"),
+            id: FixedId(42),
+            location: Some("src/lib.rs:1".to_string()),
+        });
+        let toml = manifest.to_toml();
+        let parsed: Manifest = toml.parse().unwrap();
+        assert_eq!(parsed, manifest);
+        assert_eq!(parsed.to_toml(), toml);
+        assert_eq!(parsed.find_id(FixedId(42)).count(), 1);
+    }
+
+    #[test]
+    fn diff() {
+        let old = Manifest::new(crate::manifest_entries![u8, u16, u32]);
+        let mut changed = ManifestEntry::of::<u16>();
+        changed.id = FixedId(1);
+        let new = Manifest::new(
+            crate::manifest_entries![u8, u64]
+                .into_iter()
+                .chain([changed.clone()]),
+        );
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, [ManifestEntry::of::<u64>()]);
+        assert_eq!(diff.removed, [ManifestEntry::of::<u32>()]);
+        assert_eq!(diff.changed, [(ManifestEntry::of::<u16>(), changed)]);
+        #[cfg(not(feature = "erase_name"))]
+        assert!(diff.to_string().starts_with(&format!(
+            "+ u64 {} 0x",
+            crate::manifest::format_version(&crate::RUSTC_VERSION)
+        )));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = "[[type]]\nname = \"u8\"\n".parse::<Manifest>().unwrap_err();
+        assert_eq!(
+            (error.line, error.message.as_str()),
+            (1, "missing key `version`")
+        );
+        let error = "name = \"u8\"".parse::<Manifest>().unwrap_err();
+        assert_eq!(error.line, 1);
+        let error = "[[type]]\nid = \"42\"".parse::<Manifest>().unwrap_err();
+        assert_eq!(error.line, 2);
+        let duplicated = Manifest::new(crate::manifest_entries![u8])
+            .to_toml()
+            .repeat(2);
+        assert!(duplicated.parse::<Manifest>().is_err());
+    }
+}
@@ -0,0 +1,439 @@
+//! A global registry of types that implement [`FixedTypeId`], keyed by [`FixedId`].
+//!
+//! When feature `registry` is enabled, every concrete type implemented by [`fixed_type_id!`](crate::fixed_type_id!)
+//! or `#[derive(FixedTypeId)]` is collected into [`TYPE_REGISTRY`] at link time, so a [`FixedId`] read off the wire
+//! can be turned back into its type name and version at runtime.
+//!
+//! The generated code refers to `::fixed_type_id::registry`, so it works with any import style,
+//! as long as the crate is a dependency under the name `fixed_type_id`.
+//!
+//! Types with generic parameters can't be registered, because there is no single type to register.
+//! A specific instantiation, like `Vec<u8>`, or a type implemented by hand, can be registered with [`register_types!`](crate::register_types!):
+//!
+//! ```rust
+//! use fixed_type_id::prelude::*;
+//! use fixed_type_id::registry;
+//!
+//! fixed_type_id::register_types!(Vec<u8>, Option<u16>);
+//!
+//! let entry = registry::lookup(<Vec<u8> as FixedTypeId>::TYPE_ID).unwrap();
+//! # #[cfg(not(feature = "erase_name"))]
+//! assert_eq!(entry.name, "alloc::vec::Vec<u8>");
+//! # #[cfg(not(feature = "erase_name"))]
+//! assert_eq!(registry::lookup_by_name("u8").unwrap().id, <u8 as FixedTypeId>::TYPE_ID);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use linkme::distributed_slice;
+
+use core::fmt;
+
+use semver::Version;
+
+use crate::hasher::FixedIdMap;
+use crate::{FixedId, FixedTypeId, FixedVersion};
+
+/// The metadata of a registered type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypeEntry {
+    /// The [`FixedTypeId::TYPE_NAME`] of the type.
+    pub name: &'static str,
+    /// The [`FixedTypeId::TYPE_ID`] of the type.
+    pub id: FixedId,
+    /// The [`FixedTypeId::TYPE_VERSION`] of the type.
+    pub version: FixedVersion,
+    /// The [`FixedTypeId::TYPE_NAME`] of the type whose id this type is declared equal to, by `equal_to`.
+    pub equal_to: Option<&'static str>,
+}
+
+impl TypeEntry {
+    /// Create the entry of a type, can be used in const context.
+    pub const fn of<T: ?Sized + FixedTypeId>() -> Self {
+        TypeEntry {
+            name: T::TYPE_NAME,
+            id: T::TYPE_ID,
+            version: T::TYPE_VERSION,
+            equal_to: None,
+        }
+    }
+
+    /// Mark that the id of this entry is declared equal to the id of `Target`.
+    ///
+    /// Entries sharing an id because of it are not reported by [`check_collisions`].
+    pub const fn equal_to<Target: ?Sized + FixedTypeId>(mut self) -> Self {
+        self.equal_to = Some(Target::TYPE_NAME);
+        self
+    }
+}
+
+/// Two distinct registered types which share the same [`FixedId`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Collision<'a> {
+    /// The shared id.
+    pub id: FixedId,
+    /// The first type.
+    pub first: &'a TypeEntry,
+    /// The second type.
+    pub second: &'a TypeEntry,
+}
+
+impl fmt::Display for Collision<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` ({}) and `{}` ({}) share the same id {:#x}",
+            self.first.name,
+            Version::from(self.first.version),
+            self.second.name,
+            Version::from(self.second.version),
+            self.id.0
+        )
+    }
+}
+
+/// All registered types in the final binary, in an unspecified order.
+#[distributed_slice]
+pub static TYPE_REGISTRY: [TypeEntry];
+
+#[doc(hidden)]
+pub mod __private {
+    pub use super::{TypeEntry, TYPE_REGISTRY};
+    pub use linkme::{self, distributed_slice};
+}
+
+/// Register types into [`TYPE_REGISTRY`] by hand.
+///
+/// Useful for specific instantiations of generic types, and types which implement [`FixedTypeId`] manually.
+#[macro_export]
+macro_rules! register_types {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            const _: () = {
+                #[$crate::registry::__private::distributed_slice($crate::registry::TYPE_REGISTRY)]
+                #[linkme(crate = $crate::registry::__private::linkme)]
+                static ENTRY: $crate::registry::TypeEntry = $crate::registry::TypeEntry::of::<$ty>();
+            };
+        )*
+    };
+}
+
+/// A lookup in the registry which didn't find exactly one type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupError {
+    /// No registered type matches.
+    NotFound,
+    /// More than one distinct registered type matches, like types sharing an id by `equal_to`,
+    /// or two versions of a type linked into the same binary.
+    Duplicate(&'static [&'static TypeEntry]),
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LookupError::NotFound => write!(f, "no registered type matches"),
+            LookupError::Duplicate(entries) => {
+                write!(f, "{} registered types match:", entries.len())?;
+                for entry in entries.iter() {
+                    write!(f, " `{}` ({})", entry.name, Version::from(entry.version))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LookupError {}
+
+// Entries with the same name and version are the same type registered twice, keep one of them.
+fn push_distinct(entries: &mut Vec<&'static TypeEntry>, entry: &'static TypeEntry) {
+    if !entries
+        .iter()
+        .any(|other| other.name == entry.name && other.version == entry.version)
+    {
+        entries.push(entry);
+    }
+}
+
+fn id_index() -> &'static FixedIdMap<Vec<&'static TypeEntry>> {
+    static INDEX: OnceLock<FixedIdMap<Vec<&'static TypeEntry>>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let mut index: FixedIdMap<Vec<&'static TypeEntry>> =
+            FixedIdMap::with_capacity_and_hasher(TYPE_REGISTRY.len(), Default::default());
+        for entry in TYPE_REGISTRY.iter() {
+            push_distinct(index.entry(entry.id).or_default(), entry);
+        }
+        index
+    })
+}
+
+fn name_index() -> &'static HashMap<&'static str, Vec<&'static TypeEntry>> {
+    static INDEX: OnceLock<HashMap<&'static str, Vec<&'static TypeEntry>>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let mut index: HashMap<&'static str, Vec<&'static TypeEntry>> =
+            HashMap::with_capacity(TYPE_REGISTRY.len());
+        for entry in TYPE_REGISTRY.iter() {
+            push_distinct(index.entry(entry.name).or_default(), entry);
+        }
+        index
+    })
+}
+
+fn single(
+    entries: Option<&'static Vec<&'static TypeEntry>>,
+) -> Result<&'static TypeEntry, LookupError> {
+    match entries.map(Vec::as_slice) {
+        None | Some([]) => Err(LookupError::NotFound),
+        Some([entry]) => Ok(entry),
+        Some(entries) => Err(LookupError::Duplicate(entries)),
+    }
+}
+
+/// Find the registered type of a [`FixedId`].
+///
+/// Fails with [`LookupError::Duplicate`] if more than one type share the id, see [`lookup_all`].
+pub fn lookup(id: FixedId) -> Result<&'static TypeEntry, LookupError> {
+    single(id_index().get(&id))
+}
+
+/// Find every distinct registered type of a [`FixedId`].
+pub fn lookup_all(id: FixedId) -> &'static [&'static TypeEntry] {
+    id_index().get(&id).map_or(&[], Vec::as_slice)
+}
+
+/// Find the registered type by its [`FixedTypeId::TYPE_NAME`].
+///
+/// Fails with [`LookupError::Duplicate`] if more than one version of the type are registered, see [`lookup_all_by_name`].
+pub fn lookup_by_name(name: &str) -> Result<&'static TypeEntry, LookupError> {
+    single(name_index().get(name))
+}
+
+/// Find every distinct registered type with the [`FixedTypeId::TYPE_NAME`].
+pub fn lookup_all_by_name(name: &str) -> &'static [&'static TypeEntry] {
+    name_index().get(name).map_or(&[], Vec::as_slice)
+}
+
+/// Iterate over all registered types.
+pub fn iter() -> impl Iterator<Item = &'static TypeEntry> {
+    TYPE_REGISTRY.iter()
+}
+
+/// Returns `true` if the [`FixedId`] belongs to a registered type.
+pub fn contains(id: FixedId) -> bool {
+    id_index().contains_key(&id)
+}
+
+/// Find every pair of distinct types sharing the same [`FixedId`] in `entries`.
+///
+/// Entries with the same name and version are the same type, and entries whose ids are declared equal
+/// by `equal_to` (directly or transitively) are expected to share ids, neither of them is a collision.
+pub fn find_collisions<'a>(entries: impl IntoIterator<Item = &'a TypeEntry>) -> Vec<Collision<'a>> {
+    let entries: Vec<&TypeEntry> = entries.into_iter().collect();
+    let mut by_name: HashMap<&str, &TypeEntry> = HashMap::with_capacity(entries.len());
+    let mut by_id: FixedIdMap<Vec<&TypeEntry>> =
+        FixedIdMap::with_capacity_and_hasher(entries.len(), Default::default());
+    for entry in &entries {
+        by_name.entry(entry.name).or_insert(entry);
+        let same_id = by_id.entry(entry.id).or_default();
+        if !same_id
+            .iter()
+            .any(|other| other.name == entry.name && other.version == entry.version)
+        {
+            same_id.push(entry);
+        }
+    }
+    // follow `equal_to` to the type which actually owns the id
+    let owner = |mut entry: &'a TypeEntry| {
+        for _ in 0..entries.len() {
+            match entry.equal_to.and_then(|name| by_name.get(name).copied()) {
+                Some(target) if target.id == entry.id => entry = target,
+                _ => break,
+            }
+        }
+        entry.name
+    };
+
+    let mut collisions = Vec::new();
+    for (id, same_id) in by_id {
+        for (index, first) in same_id.iter().enumerate() {
+            for second in &same_id[index + 1..] {
+                if first.name != second.name && owner(first) == owner(second) {
+                    continue;
+                }
+                let (first, second) =
+                    if (first.name, first.version) <= (second.name, second.version) {
+                        (first, second)
+                    } else {
+                        (second, first)
+                    };
+                collisions.push(Collision { id, first, second });
+            }
+        }
+    }
+    collisions.sort_by_key(|c| {
+        (
+            c.id,
+            c.first.name,
+            c.first.version,
+            c.second.name,
+            c.second.version,
+        )
+    });
+    collisions
+}
+
+/// Find every pair of distinct registered types sharing the same [`FixedId`], see [`find_collisions`].
+pub fn check_collisions() -> Vec<Collision<'static>> {
+    find_collisions(TYPE_REGISTRY.iter())
+}
+
+/// Panics if any two distinct registered types share the same [`FixedId`].
+///
+/// Call it in a test of your final binary to make sure no type name, version or `#[random_id]` collides:
+///
+/// ```rust
+/// // inside a `#[test]` function
+/// fixed_type_id::registry::assert_no_collisions();
+/// ```
+#[track_caller]
+pub fn assert_no_collisions() {
+    let collisions = check_collisions();
+    if !collisions.is_empty() {
+        let report: Vec<String> = collisions.iter().map(|c| format!("  {}", c)).collect();
+        panic!(
+            "found {} FixedId collision(s) in the registry:\n{}",
+            collisions.len(),
+            report.join("\n")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::registry::{
+        assert_no_collisions, find_collisions, iter, lookup, lookup_all, lookup_all_by_name,
+        lookup_by_name, LookupError, TypeEntry,
+    };
+
+    mod registered {
+        use crate::prelude::*;
+
+        pub trait Registered {}
+        pub struct A;
+        pub struct Alias;
+
+        fixed_type_id! {
+            #[version((0,3,0))]
+            tests::registry::A;
+            dyn tests::registry::Registered;
+        }
+        fixed_type_id! {
+            #[version((0,3,0))]
+            #[equal_to(A)]
+            tests::registry::Alias;
+        }
+
+        #[derive(FixedTypeId)]
+        #[fixed_type_id(name = "tests::registry::B", version = "1.0.0")]
+        pub struct B;
+    }
+    use registered::*;
+
+    #[test]
+    fn lookup_registered() {
+        let entry = lookup_by_name(<A as FixedTypeId>::TYPE_NAME).unwrap();
+        assert_eq!(*entry, TypeEntry::of::<A>());
+        assert_eq!(entry.version, FixedVersion::new(0, 3, 0));
+
+        let entry = lookup(<dyn Registered as FixedTypeId>::TYPE_ID).unwrap();
+        assert_eq!(*entry, TypeEntry::of::<dyn Registered>());
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(entry.name, "dyn tests::registry::Registered");
+
+        let entry = lookup_by_name(<B as FixedTypeId>::TYPE_NAME).unwrap();
+        assert_eq!(entry.id, <B as FixedTypeId>::TYPE_ID);
+        assert_eq!(entry.version, FixedVersion::new(1, 0, 0));
+
+        // primitive types are registered by this crate
+        assert_eq!(
+            lookup_by_name(<u32 as FixedTypeId>::TYPE_NAME).unwrap().id,
+            <u32 as FixedTypeId>::TYPE_ID
+        );
+        assert!(iter().any(|entry| entry.name == <core::time::Duration as FixedTypeId>::TYPE_NAME));
+
+        let unknown = FixedId::from_type_name("tests::registry::Unknown", None);
+        assert_eq!(lookup(unknown), Err(LookupError::NotFound));
+        assert!(lookup_all(unknown).is_empty());
+        assert_eq!(
+            lookup_by_name("tests::registry::Unknown"),
+            Err(LookupError::NotFound)
+        );
+    }
+
+    #[test]
+    fn lookup_duplicates() {
+        let id = <A as FixedTypeId>::TYPE_ID;
+        let mut names: Vec<_> = lookup_all(id).iter().map(|entry| entry.name).collect();
+        names.sort();
+        let mut expected = [
+            <A as FixedTypeId>::TYPE_NAME,
+            <Alias as FixedTypeId>::TYPE_NAME,
+        ];
+        expected.sort();
+        assert_eq!(names, expected);
+        assert_eq!(lookup(id), Err(LookupError::Duplicate(lookup_all(id))));
+        assert_eq!(
+            lookup_all_by_name(<Alias as FixedTypeId>::TYPE_NAME).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn collisions() {
+        let entry = |name: &'static str, version: (u64, u64, u64), id: u64| TypeEntry {
+            name,
+            id: FixedId::from_u64(id),
+            version: version.into(),
+            equal_to: None,
+        };
+        let a = entry("A", (0, 1, 0), 1);
+        let a_again = entry("A", (0, 1, 0), 1);
+        let a_new = entry("A", (0, 2, 0), 1);
+        let b = entry("B", (0, 1, 0), 1);
+        let c = entry("C", (0, 1, 0), 2);
+        let alias = TypeEntry {
+            equal_to: Some("A"),
+            ..entry("Alias", (0, 1, 0), 1)
+        };
+        let alias_of_alias = TypeEntry {
+            equal_to: Some("Alias"),
+            ..entry("AliasOfAlias", (0, 1, 0), 1)
+        };
+
+        assert!(find_collisions([&a, &a_again, &c]).is_empty());
+        assert!(find_collisions([&a, &alias, &alias_of_alias, &c]).is_empty());
+
+        let collisions = find_collisions([&a, &a_new, &b, &c, &alias]);
+        let pairs: Vec<_> = collisions
+            .iter()
+            .map(|c| (c.first.name, c.first.version, c.second.name))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("A", FixedVersion::new(0, 1, 0), "A"),
+                ("A", FixedVersion::new(0, 1, 0), "B"),
+                ("A", FixedVersion::new(0, 2, 0), "B"),
+                ("Alias", FixedVersion::new(0, 1, 0), "B"),
+            ]
+        );
+        assert!(collisions.iter().all(|c| c.id == FixedId(1)));
+    }
+
+    #[test]
+    fn no_collisions_in_registry() {
+        assert_no_collisions();
+    }
+}
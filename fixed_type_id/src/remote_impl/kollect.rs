@@ -8,11 +8,11 @@ fixed_type_id!(
 );
 
 impl<K: FixedTypeId, V: FixedTypeId, S> FixedTypeId for OrderedMap<K, V, S> {
-    const TYPE_NAME: &'static str = fstr_to_str(&Self::TYPE_NAME_FSTR);
+    const TYPE_NAME: &'static str = Self::TYPE_NAME_STR;
 }
 
 impl<K: FixedTypeId, S> FixedTypeId for OrderedSet<K, S> {
-    const TYPE_NAME: &'static str = fstr_to_str(&Self::TYPE_NAME_FSTR);
+    const TYPE_NAME: &'static str = Self::TYPE_NAME_STR;
 }
 
 impl<K: FixedTypeId, V: FixedTypeId, S> ConstTypeName for OrderedMap<K, V, S> {
@@ -24,11 +24,11 @@ impl<K: FixedTypeId, S> ConstTypeName for OrderedSet<K, S> {
 }
 
 impl<K: FixedTypeId, V: FixedTypeId, S> FixedTypeId for UnorderedMap<K, V, S> {
-    const TYPE_NAME: &'static str = fstr_to_str(&Self::TYPE_NAME_FSTR);
+    const TYPE_NAME: &'static str = Self::TYPE_NAME_STR;
 }
 
 impl<K: FixedTypeId, S> FixedTypeId for UnorderedSet<K, S> {
-    const TYPE_NAME: &'static str = fstr_to_str(&Self::TYPE_NAME_FSTR);
+    const TYPE_NAME: &'static str = Self::TYPE_NAME_STR;
 }
 
 impl<K: FixedTypeId, V: FixedTypeId, S> ConstTypeName for UnorderedMap<K, V, S> {
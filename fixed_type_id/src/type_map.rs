@@ -0,0 +1,489 @@
+//! A map which stores at most one value of each type, keyed by [`FixedTypeId::TYPE_ID`].
+//!
+//! Unlike maps keyed by [`core::any::TypeId`], the keys of [`FixedTypeMap`] are stable between builds,
+//! so they can be saved in a snapshot and looked up again after reloading.
+//! Types with the same id, like types declared with `equal_to`, share an entry: a value is only returned as its own type,
+//! and inserting a value of another type with the same id replaces it.
+//!
+//! ```rust
+//! use fixed_type_id::prelude::*;
+//! use fixed_type_id::FixedTypeMap;
+//!
+//! let mut map = FixedTypeMap::new();
+//! assert_eq!(map.insert(1u32), None);
+//! assert_eq!(map.insert(2u32), Some(1));
+//! map.insert(String::from("fixed"));
+//! *map.get_mut::<u32>().unwrap() += 1;
+//! assert_eq!(map.get::<u32>(), Some(&3));
+//! map.entry::<Vec<u8>>().or_default().push(1);
+//! assert_eq!(map.remove::<Vec<u8>>(), Some(vec![1]));
+//! assert_eq!(map.len(), 2);
+//! ```
+
+use core::any::{Any, TypeId};
+use core::fmt;
+use core::marker::PhantomData;
+use std::collections::hash_map;
+
+use crate::hasher::FixedIdMap;
+use crate::{FixedAny, FixedId, FixedTypeId};
+
+type RawMap<A> = FixedIdMap<Box<A>>;
+
+/// Trait objects of [`FixedAny`] which can be stored in a [`Map`].
+///
+/// It's implemented for `dyn FixedAny`, `dyn FixedAny + Send` and `dyn FixedAny + Send + Sync`.
+pub trait Downcast: FixedAny {
+    /// Returns a reference to the value as type `T`.
+    ///
+    /// # Safety
+    ///
+    /// The underlying value must be of type `T`.
+    #[inline]
+    unsafe fn downcast_ref_unchecked<T: FixedAny>(&self) -> &T {
+        unsafe { &*(self as *const Self as *const T) }
+    }
+
+    /// Returns a mutable reference to the value as type `T`.
+    ///
+    /// # Safety
+    ///
+    /// The underlying value must be of type `T`.
+    #[inline]
+    unsafe fn downcast_mut_unchecked<T: FixedAny>(&mut self) -> &mut T {
+        unsafe { &mut *(self as *mut Self as *mut T) }
+    }
+
+    /// Returns the value as a box of type `T`.
+    ///
+    /// # Safety
+    ///
+    /// The underlying value must be of type `T`.
+    #[inline]
+    unsafe fn downcast_unchecked<T: FixedAny>(self: Box<Self>) -> Box<T> {
+        unsafe { Box::from_raw(Box::into_raw(self) as *mut T) }
+    }
+}
+
+/// Returns `true` if the value is of type `T`, an entry can hold a value of another type with the same id.
+#[inline]
+fn is<A: ?Sized + Downcast, T: 'static>(value: &A) -> bool {
+    Any::type_id(value) == TypeId::of::<T>()
+}
+
+/// Values which can be boxed into the trait object `A`.
+pub trait IntoBox<A: ?Sized + Downcast>: FixedAny {
+    /// Box the value into the trait object.
+    fn into_box(self) -> Box<A>;
+}
+
+macro_rules! impl_downcast {
+    ($($dyn_type:ty => ($($bound:tt)*)),*) => {
+        $(
+            impl Downcast for $dyn_type {}
+
+            impl<T: FixedAny $($bound)*> IntoBox<$dyn_type> for T {
+                #[inline]
+                fn into_box(self) -> Box<$dyn_type> {
+                    Box::new(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_downcast!(
+    dyn FixedAny => (),
+    dyn FixedAny + Send => (+ Send),
+    dyn FixedAny + Send + Sync => (+ Send + Sync)
+);
+
+/// A map which stores at most one value of each type, keyed by [`FixedTypeId::TYPE_ID`].
+///
+/// The type parameter `A` is the trait object the values are stored as, usually used by its aliases
+/// [`FixedTypeMap`], [`SendFixedTypeMap`] and [`SyncFixedTypeMap`].
+pub struct Map<A: ?Sized + Downcast = dyn FixedAny> {
+    raw: RawMap<A>,
+}
+
+/// A [`Map`] of any values.
+pub type FixedTypeMap = Map<dyn FixedAny>;
+
+/// A [`Map`] of `Send` values, which is `Send`.
+pub type SendFixedTypeMap = Map<dyn FixedAny + Send>;
+
+/// A [`Map`] of `Send + Sync` values, which is `Send` and `Sync`.
+pub type SyncFixedTypeMap = Map<dyn FixedAny + Send + Sync>;
+
+impl<A: ?Sized + Downcast> Default for Map<A> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: ?Sized + Downcast> fmt::Debug for Map<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.raw.values().map(|value| (**value).fixed_name()))
+            .finish()
+    }
+}
+
+impl<A: ?Sized + Downcast> Map<A> {
+    /// Create an empty map.
+    #[inline]
+    pub fn new() -> Self {
+        Map {
+            raw: RawMap::default(),
+        }
+    }
+
+    /// Create an empty map with at least the specified capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Map {
+            raw: RawMap::with_capacity_and_hasher(capacity, Default::default()),
+        }
+    }
+
+    /// Returns the number of values in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.raw.len()
+    }
+
+    /// Returns `true` if the map contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.raw.is_empty()
+    }
+
+    /// Removes all values.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.raw.clear()
+    }
+
+    /// Returns `true` if the map contains a value of type `T`.
+    #[inline]
+    pub fn contains<T: FixedTypeId + 'static>(&self) -> bool {
+        self.raw
+            .get(&T::TYPE_ID)
+            .is_some_and(|value| is::<A, T>(value))
+    }
+
+    /// Returns `true` if the map contains a value with the [`FixedId`].
+    #[inline]
+    pub fn contains_id(&self, id: FixedId) -> bool {
+        self.raw.contains_key(&id)
+    }
+
+    /// Insert a value, returns the previous value of the same type if there is one.
+    ///
+    /// A value of another type with the same id is replaced and dropped.
+    #[inline]
+    pub fn insert<T: FixedTypeId + IntoBox<A>>(&mut self, value: T) -> Option<T> {
+        let previous = self.raw.insert(T::TYPE_ID, value.into_box())?;
+        if is::<A, T>(&previous) {
+            // SAFETY: the value is of type `T`.
+            Some(*unsafe { previous.downcast_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the value of type `T`.
+    #[inline]
+    pub fn get<T: FixedTypeId + IntoBox<A>>(&self) -> Option<&T> {
+        let value = self.raw.get(&T::TYPE_ID)?;
+        if is::<A, T>(value) {
+            // SAFETY: the value is of type `T`.
+            Some(unsafe { value.downcast_ref_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the value of type `T`.
+    #[inline]
+    pub fn get_mut<T: FixedTypeId + IntoBox<A>>(&mut self) -> Option<&mut T> {
+        let value = self.raw.get_mut(&T::TYPE_ID)?;
+        if is::<A, T>(value) {
+            // SAFETY: the value is of type `T`.
+            Some(unsafe { value.downcast_mut_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// Removes the value of type `T` and returns it.
+    #[inline]
+    pub fn remove<T: FixedTypeId + IntoBox<A>>(&mut self) -> Option<T> {
+        match self.raw.entry(T::TYPE_ID) {
+            hash_map::Entry::Occupied(inner) if is::<A, T>(inner.get()) => {
+                // SAFETY: the value is of type `T`.
+                Some(*unsafe { inner.remove().downcast_unchecked() })
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the entry of type `T` for in-place manipulation.
+    ///
+    /// The entry is vacant if it holds a value of another type with the same id, which is replaced on insertion.
+    #[inline]
+    pub fn entry<T: FixedTypeId + IntoBox<A>>(&mut self) -> Entry<'_, A, T> {
+        match self.raw.entry(T::TYPE_ID) {
+            hash_map::Entry::Occupied(inner) if is::<A, T>(inner.get()) => {
+                Entry::Occupied(OccupiedEntry {
+                    inner,
+                    _marker: PhantomData,
+                })
+            }
+            hash_map::Entry::Occupied(inner) => Entry::Vacant(VacantEntry {
+                inner: hash_map::Entry::Occupied(inner),
+                _marker: PhantomData,
+            }),
+            hash_map::Entry::Vacant(inner) => Entry::Vacant(VacantEntry {
+                inner: hash_map::Entry::Vacant(inner),
+                _marker: PhantomData,
+            }),
+        }
+    }
+
+    /// Iterate over the values as trait objects, in an unspecified order.
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = &A> {
+        self.raw.values().map(|value| &**value)
+    }
+}
+
+/// A view into a single entry of a [`Map`], see [`Map::entry`].
+pub enum Entry<'a, A: ?Sized + Downcast, T> {
+    /// The map contains a value of type `T`.
+    Occupied(OccupiedEntry<'a, A, T>),
+    /// The map doesn't contain a value of type `T`.
+    Vacant(VacantEntry<'a, A, T>),
+}
+
+/// An occupied entry of a [`Map`], which holds a value of type `T`.
+pub struct OccupiedEntry<'a, A: ?Sized + Downcast, T> {
+    inner: hash_map::OccupiedEntry<'a, FixedId, Box<A>>,
+    _marker: PhantomData<T>,
+}
+
+/// A vacant entry of a [`Map`], it may hold a value of another type with the same id.
+pub struct VacantEntry<'a, A: ?Sized + Downcast, T> {
+    inner: hash_map::Entry<'a, FixedId, Box<A>>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, A: ?Sized + Downcast, T: FixedTypeId + IntoBox<A>> Entry<'a, A, T> {
+    /// Insert `default` if the entry is vacant, and returns a mutable reference to the value.
+    #[inline]
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Insert the result of `default` if the entry is vacant, and returns a mutable reference to the value.
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Modify the value in place if the entry is occupied.
+    #[inline]
+    pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, A: ?Sized + Downcast, T: FixedTypeId + IntoBox<A> + Default> Entry<'a, A, T> {
+    /// Insert the default value if the entry is vacant, and returns a mutable reference to the value.
+    #[inline]
+    pub fn or_default(self) -> &'a mut T {
+        self.or_insert_with(T::default)
+    }
+}
+
+impl<'a, A: ?Sized + Downcast, T: FixedTypeId + IntoBox<A>> OccupiedEntry<'a, A, T> {
+    /// Returns a reference to the value.
+    #[inline]
+    pub fn get(&self) -> &T {
+        // SAFETY: the occupied entry holds a value of type `T`.
+        unsafe { self.inner.get().downcast_ref_unchecked() }
+    }
+
+    /// Returns a mutable reference to the value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        // SAFETY: the occupied entry holds a value of type `T`.
+        unsafe { self.inner.get_mut().downcast_mut_unchecked() }
+    }
+
+    /// Converts the entry into a mutable reference to the value, with the lifetime of the map.
+    #[inline]
+    pub fn into_mut(self) -> &'a mut T {
+        // SAFETY: the occupied entry holds a value of type `T`.
+        unsafe { self.inner.into_mut().downcast_mut_unchecked() }
+    }
+
+    /// Replace the value, and returns the old value.
+    #[inline]
+    pub fn insert(&mut self, value: T) -> T {
+        // SAFETY: the occupied entry holds a value of type `T`.
+        *unsafe { self.inner.insert(value.into_box()).downcast_unchecked() }
+    }
+
+    /// Removes the value from the map and returns it.
+    #[inline]
+    pub fn remove(self) -> T {
+        // SAFETY: the occupied entry holds a value of type `T`.
+        *unsafe { self.inner.remove().downcast_unchecked() }
+    }
+}
+
+impl<'a, A: ?Sized + Downcast, T: FixedTypeId + IntoBox<A>> VacantEntry<'a, A, T> {
+    /// Insert the value, and returns a mutable reference to it.
+    #[inline]
+    pub fn insert(self, value: T) -> &'a mut T {
+        let value = match self.inner {
+            hash_map::Entry::Occupied(mut inner) => {
+                inner.insert(value.into_box());
+                inner.into_mut()
+            }
+            hash_map::Entry::Vacant(inner) => inner.insert(value.into_box()),
+        };
+        // SAFETY: the value was just inserted as type `T`.
+        unsafe { value.downcast_mut_unchecked() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::type_map::{Entry, FixedTypeMap, SendFixedTypeMap, SyncFixedTypeMap};
+
+    mod same_id {
+        use crate::prelude::*;
+
+        #[derive(Debug, PartialEq)]
+        pub struct V1(pub u8);
+        #[derive(Debug, PartialEq, Default)]
+        pub struct V2(pub u16);
+
+        fixed_type_id! {
+            #[version((1,0,0))]
+            #[omit_version_hash]
+            tests::type_map::V1;
+        }
+        fixed_type_id! {
+            #[version((2,0,0))]
+            #[equal_to(V1)]
+            tests::type_map::V2;
+        }
+    }
+    use same_id::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = FixedTypeMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert(1u8), None);
+        assert_eq!(map.insert(2u16), None);
+        assert_eq!(map.insert(3u8), Some(1));
+        assert_eq!(map.len(), 2);
+        assert!(map.contains::<u8>());
+        assert!(map.contains_id(<u16 as FixedTypeId>::TYPE_ID));
+        assert!(!map.contains::<u32>());
+
+        assert_eq!(map.get::<u8>(), Some(&3));
+        assert_eq!(map.get::<u32>(), None);
+        *map.get_mut::<u16>().unwrap() += 1;
+        assert_eq!(map.remove::<u16>(), Some(3));
+        assert_eq!(map.remove::<u16>(), None);
+        assert_eq!(map.values().count(), 1);
+        // the keys are printed by their names
+        #[cfg(not(feature = "erase_name"))]
+        assert_eq!(format!("{:?}", map), "{\"u8\"}");
+
+        map.clear();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn entry() {
+        let mut map = FixedTypeMap::with_capacity(4);
+        map.entry::<Vec<u8>>().or_default().push(1);
+        map.entry::<Vec<u8>>()
+            .and_modify(|value| value.push(2))
+            .or_insert_with(|| vec![0])
+            .push(3);
+        assert_eq!(map.get::<Vec<u8>>().unwrap(), &[1, 2, 3]);
+        assert_eq!(*map.entry::<u32>().or_insert(7), 7);
+
+        match map.entry::<u32>() {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(*entry.get(), 7);
+                assert_eq!(entry.insert(8), 7);
+                assert_eq!(entry.remove(), 8);
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match map.entry::<u32>() {
+            Entry::Occupied(_) => unreachable!(),
+            Entry::Vacant(entry) => *entry.insert(9) += 1,
+        }
+        assert_eq!(map.get::<u32>(), Some(&10));
+    }
+
+    #[test]
+    fn send_sync() {
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let mut map = SendFixedTypeMap::new();
+        map.insert(String::from("send"));
+        assert_send(&map);
+
+        let mut map = SyncFixedTypeMap::default();
+        map.insert(String::from("sync"));
+        assert_send(&map);
+        assert_sync(&map);
+        let map = std::thread::spawn(move || map).join().unwrap();
+        assert_eq!(map.get::<String>().unwrap(), "sync");
+    }
+
+    #[test]
+    fn same_id() {
+        let mut map = FixedTypeMap::new();
+        map.insert(V1(1));
+        assert!(map.contains::<V1>());
+        assert!(!map.contains::<V2>());
+        assert!(map.contains_id(<V2 as FixedTypeId>::TYPE_ID));
+        assert_eq!(map.get::<V2>(), None);
+        assert_eq!(map.get_mut::<V2>(), None);
+        assert_eq!(map.remove::<V2>(), None);
+        assert!(matches!(map.entry::<V2>(), Entry::Vacant(_)));
+        assert_eq!(map.get::<V1>(), Some(&V1(1)));
+
+        // a value of another type with the same id is replaced
+        assert_eq!(map.insert(V2(2)), None);
+        assert_eq!(map.get::<V1>(), None);
+        assert_eq!(map.len(), 1);
+        assert_eq!(*map.entry::<V1>().or_insert(V1(3)), V1(3));
+        assert_eq!(map.entry::<V2>().or_default(), &mut V2(0));
+        assert_eq!(map.remove::<V2>(), Some(V2(0)));
+        assert!(map.is_empty());
+    }
+}
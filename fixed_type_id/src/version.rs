@@ -0,0 +1,485 @@
+//! The pre-release part of a [`FixedVersion`], and the `major.minor.patch[-pre]` text form of versions.
+//!
+//! ```rust
+//! use fixed_type_id::FixedVersion;
+//!
+//! const BETA: FixedVersion = FixedVersion::new_pre(2, 0, 0, "beta.3");
+//!
+//! assert_eq!(BETA.to_string(), "2.0.0-beta.3");
+//! assert_eq!("2.0.0-beta.3".parse(), Ok(BETA));
+//! assert!(BETA < FixedVersion::new(2, 0, 0));
+//! ```
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::FixedVersion;
+
+pub use crate::algorithm::MAX_PRERELEASE_LEN;
+
+/// The pre-release of a [`FixedVersion`], like `beta.3` of `2.0.0-beta.3`, empty for a release.
+///
+/// It's stored inline, so [`FixedVersion`] stays `Copy` and can be created in const context,
+/// its length is limited by [`MAX_PRERELEASE_LEN`].
+///
+/// It's ordered by semver precedence: the identifiers are compared one by one, numerically if both are numeric,
+/// and an empty pre-release, a release, is greater than any pre-release.
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(feature = "rkyv", rkyv(attr(allow(missing_docs))))]
+#[cfg_attr(feature = "rkyv", rkyv(compare(PartialEq), derive(Debug)))]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FixedPrerelease {
+    bytes: [u8; MAX_PRERELEASE_LEN],
+    len: u8,
+}
+
+impl FixedPrerelease {
+    /// The empty pre-release, of a release.
+    pub const EMPTY: FixedPrerelease = FixedPrerelease {
+        bytes: [0; MAX_PRERELEASE_LEN],
+        len: 0,
+    };
+
+    /// Create a pre-release from dot separated identifiers like `beta.3`, can be used in const context.
+    ///
+    /// Panics if it's not a valid semver pre-release, or it's longer than [`MAX_PRERELEASE_LEN`].
+    pub const fn new(pre: &str) -> Self {
+        match Self::parse(pre) {
+            Ok(pre) => pre,
+            Err(ParseVersionError::PrereleaseTooLong) => {
+                panic!("the pre-release is longer than `MAX_PRERELEASE_LEN` (32) bytes")
+            }
+            Err(_) => {
+                panic!("invalid pre-release, expected dot separated identifiers like `beta.3`")
+            }
+        }
+    }
+
+    /// Parse a pre-release from dot separated identifiers like `beta.3`, an empty string is [`FixedPrerelease::EMPTY`].
+    pub const fn parse(pre: &str) -> Result<Self, ParseVersionError> {
+        let pre = pre.as_bytes();
+        if pre.len() > MAX_PRERELEASE_LEN {
+            return Err(ParseVersionError::PrereleaseTooLong);
+        }
+        if !pre.is_empty() && !valid_identifiers(pre, true) {
+            return Err(ParseVersionError::InvalidPrerelease);
+        }
+        let mut bytes = [0; MAX_PRERELEASE_LEN];
+        let mut i = 0;
+        while i < pre.len() {
+            bytes[i] = pre[i];
+            i += 1;
+        }
+        Ok(FixedPrerelease {
+            bytes,
+            len: pre.len() as u8,
+        })
+    }
+
+    /// Get the pre-release as a string, empty for a release.
+    pub const fn as_str(&self) -> &str {
+        // SAFETY: the bytes are checked to be ASCII when created.
+        unsafe { core::str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Get the bytes of the pre-release, they are hashed into the ids of pre-release versions.
+    pub const fn as_bytes(&self) -> &[u8] {
+        self.bytes.split_at(self.len as usize).0
+    }
+
+    /// If it's the empty pre-release of a release.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Default for FixedPrerelease {
+    fn default() -> Self {
+        FixedPrerelease::EMPTY
+    }
+}
+
+impl Ord for FixedPrerelease {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            (false, false) => {}
+        }
+        let mut lhs = self.as_str().split('.');
+        let mut rhs = other.as_str().split('.');
+        loop {
+            let (lhs, rhs) = match (lhs.next(), rhs.next()) {
+                (Some(lhs), Some(rhs)) => (lhs, rhs),
+                // a shorter pre-release is smaller, if all the identifiers before are equal
+                (lhs, rhs) => return lhs.is_some().cmp(&rhs.is_some()),
+            };
+            let is_numeric = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+            // numeric identifiers have no leading zeros, so they are compared by length first,
+            // and are smaller than alphanumeric ones
+            let ordering = match (is_numeric(lhs), is_numeric(rhs)) {
+                (true, true) => lhs.len().cmp(&rhs.len()).then_with(|| lhs.cmp(rhs)),
+                (true, false) => Ordering::Less,
+                (false, true) => Ordering::Greater,
+                (false, false) => lhs.cmp(rhs),
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+    }
+}
+
+impl PartialOrd for FixedPrerelease {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for FixedPrerelease {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FixedPrerelease")
+            .field(&self.as_str())
+            .finish()
+    }
+}
+
+impl fmt::Display for FixedPrerelease {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FixedPrerelease {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FixedPrerelease::parse(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FixedPrerelease {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FixedPrerelease {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PrereleaseVisitor;
+
+        impl serde::de::Visitor<'_> for PrereleaseVisitor {
+            type Value = FixedPrerelease;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a semver pre-release like `beta.3`")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                FixedPrerelease::parse(v).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(PrereleaseVisitor)
+    }
+}
+
+/// An error when parsing a [`FixedVersion`] or a [`FixedPrerelease`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseVersionError {
+    /// The version is not like `major.minor.patch[-pre]`.
+    InvalidFormat,
+    /// The pre-release is not dot separated identifiers of `[0-9A-Za-z-]`,
+    /// or a numeric identifier has leading zeros.
+    InvalidPrerelease,
+    /// The pre-release is longer than [`MAX_PRERELEASE_LEN`].
+    PrereleaseTooLong,
+}
+
+impl fmt::Display for ParseVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseVersionError::InvalidFormat => f.write_str(
+                "invalid version, expected `major.minor.patch` or `major.minor.patch-pre`",
+            ),
+            ParseVersionError::InvalidPrerelease => {
+                f.write_str("invalid pre-release, expected dot separated identifiers like `beta.3`")
+            }
+            ParseVersionError::PrereleaseTooLong => write!(
+                f,
+                "the pre-release is longer than `MAX_PRERELEASE_LEN` ({MAX_PRERELEASE_LEN}) bytes"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ParseVersionError {}
+
+impl FixedVersion {
+    /// Parse a version like `1.2.0` or `2.0.0-beta.3`, can be used in const context.
+    ///
+    /// The build metadata like `+build.5` is accepted but dropped, as it doesn't take part in version precedence.
+    pub const fn parse(s: &str) -> Result<Self, ParseVersionError> {
+        let bytes = s.as_bytes();
+        // the end of the build metadata is the end of the string, the pre-release ends at the build metadata
+        let mut build = bytes.len();
+        let mut pre = bytes.len();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'+' {
+                build = i;
+                break;
+            }
+            if bytes[i] == b'-' && pre == bytes.len() {
+                pre = i;
+            }
+            i += 1;
+        }
+        if pre > build {
+            pre = build;
+        }
+        if build < bytes.len() && !valid_identifiers(bytes.split_at(build + 1).1, false) {
+            return Err(ParseVersionError::InvalidFormat);
+        }
+        let (numbers, _) = bytes.split_at(pre);
+        let mut parts = [0u64; 3];
+        let mut part = 0;
+        let mut start = 0;
+        let mut i = 0;
+        while i <= numbers.len() {
+            if i == numbers.len() || numbers[i] == b'.' {
+                if part == 3 {
+                    return Err(ParseVersionError::InvalidFormat);
+                }
+                let (_, rest) = numbers.split_at(start);
+                let (number, _) = rest.split_at(i - start);
+                parts[part] = match parse_number(number) {
+                    Some(number) => number,
+                    None => return Err(ParseVersionError::InvalidFormat),
+                };
+                part += 1;
+                start = i + 1;
+            }
+            i += 1;
+        }
+        if part != 3 {
+            return Err(ParseVersionError::InvalidFormat);
+        }
+        let pre = if pre < build {
+            let (_, rest) = bytes.split_at(pre + 1);
+            let (pre, _) = rest.split_at(build - pre - 1);
+            // SAFETY: the string is split at ASCII characters.
+            match FixedPrerelease::parse(unsafe { core::str::from_utf8_unchecked(pre) }) {
+                Ok(pre) if !pre.is_empty() => pre,
+                Ok(_) => return Err(ParseVersionError::InvalidPrerelease),
+                Err(error) => return Err(error),
+            }
+        } else {
+            FixedPrerelease::EMPTY
+        };
+        Ok(FixedVersion {
+            major: parts[0],
+            minor: parts[1],
+            patch: parts[2],
+            pre,
+        })
+    }
+}
+
+/// Formats the version as `major.minor.patch`, followed by `-pre` for a pre-release.
+impl fmt::Display for FixedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FixedVersion {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FixedVersion::parse(s)
+    }
+}
+
+/// Parse a decimal number without leading zeros.
+const fn parse_number(bytes: &[u8]) -> Option<u64> {
+    if bytes.is_empty() || (bytes.len() > 1 && bytes[0] == b'0') {
+        return None;
+    }
+    let mut number: u64 = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            return None;
+        }
+        number = match number.checked_mul(10) {
+            Some(number) => match number.checked_add((bytes[i] - b'0') as u64) {
+                Some(number) => number,
+                None => return None,
+            },
+            None => return None,
+        };
+        i += 1;
+    }
+    Some(number)
+}
+
+/// If the bytes are non-empty dot separated identifiers of `[0-9A-Za-z-]`,
+/// numeric identifiers of a pre-release can't have leading zeros.
+const fn valid_identifiers(bytes: &[u8], is_pre: bool) -> bool {
+    let mut start = 0;
+    let mut i = 0;
+    while i <= bytes.len() {
+        if i == bytes.len() || bytes[i] == b'.' {
+            let len = i - start;
+            if len == 0 {
+                return false;
+            }
+            if is_pre && len > 1 && bytes[start] == b'0' {
+                let mut numeric = true;
+                let mut j = start;
+                while j < i {
+                    numeric &= bytes[j].is_ascii_digit();
+                    j += 1;
+                }
+                if numeric {
+                    return false;
+                }
+            }
+            start = i + 1;
+        } else if !bytes[i].is_ascii_alphanumeric() && bytes[i] != b'-' {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::{FixedPrerelease, ParseVersionError};
+    use crate::{name_version_to_hash, FixedId, FixedVersion};
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse_and_display() {
+        for s in [
+            "0.0.0",
+            "1.2.3",
+            "2.0.0-beta.3",
+            "1.0.0-alpha-1.0a",
+            "1.0.0-0.3.7",
+        ] {
+            let version: FixedVersion = s.parse().unwrap();
+            assert_eq!(version.to_string(), s);
+        }
+        assert_eq!(
+            "2.0.0-rc.1+build.5".parse(),
+            Ok(FixedVersion::new_pre(2, 0, 0, "rc.1"))
+        );
+        assert_eq!("1.2.3+5".parse(), Ok(FixedVersion::new(1, 2, 3)));
+        for s in [
+            "", "1", "1.2", "1.2.3.4", "01.2.3", "1.2.x", "1.2.3+", "-1.2.3", "1.2.3++",
+        ] {
+            assert_eq!(
+                s.parse::<FixedVersion>(),
+                Err(ParseVersionError::InvalidFormat),
+                "{s}"
+            );
+        }
+        for s in ["1.2.3-", "1.2.3-beta..1", "1.2.3-beta.01", "1.2.3-b_1"] {
+            assert_eq!(
+                s.parse::<FixedVersion>(),
+                Err(ParseVersionError::InvalidPrerelease),
+                "{s}"
+            );
+        }
+        assert_eq!(
+            "1.2.3-abcdefghijklmnopqrstuvwxyz.0123456".parse::<FixedVersion>(),
+            Err(ParseVersionError::PrereleaseTooLong)
+        );
+    }
+
+    #[test]
+    fn prerelease_precedence() {
+        // the example of the semver spec
+        let versions = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1-alpha",
+        ]
+        .map(|s| s.parse::<FixedVersion>().unwrap());
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(FixedPrerelease::default(), FixedPrerelease::EMPTY);
+        assert_eq!(FixedPrerelease::new("beta.3").as_str(), "beta.3");
+    }
+
+    #[test]
+    fn prerelease_hashed() {
+        let beta = FixedVersion::new_pre(2, 0, 0, "beta.3");
+        let id = FixedId::from_type_name("A", Some(beta));
+        assert_eq!(id.0, name_version_to_hash("A", &beta));
+        assert_ne!(
+            id,
+            FixedId::from_type_name("A", Some(FixedVersion::new(2, 0, 0)))
+        );
+        assert_ne!(
+            id,
+            FixedId::from_type_name("A", Some(FixedVersion::new_pre(2, 0, 0, "beta.4")))
+        );
+    }
+
+    #[test]
+    fn satisfies_requirements() {
+        let req = |s: &str| semver::VersionReq::parse(s).unwrap();
+        let version = |s: &str| s.parse::<FixedVersion>().unwrap();
+        assert!(version("1.4.2").satisfies(&req(">=1.2, <2")));
+        assert!(!version("2.0.0").satisfies(&req(">=1.2, <2")));
+        // a pre-release only satisfies the requirements of the same `major.minor.patch` with a pre-release
+        assert!(!version("2.0.0-beta.3").satisfies(&req(">=1.2")));
+        assert!(version("2.0.0-beta.3").satisfies(&req(">=2.0.0-beta.1, <2.0.0")));
+        assert!(!version("2.0.0-beta.3").satisfies(&req(">=2.0.0-beta.4")));
+        assert!(version("2.0.0-beta.3").is_compatible(&version("2.0.0-beta.1")));
+        assert!(!version("2.0.0-beta.3").is_compatible(&version("2.0.0")));
+        assert!(version("1.3.0").is_compatible(&version("1.2.0")));
+    }
+
+    #[test]
+    fn long_semver_prerelease() {
+        let semver =
+            semver::Version::parse("1.0.0-alpha.abcdefghij.abcdefghij.abcdefghij").unwrap();
+        assert_eq!(
+            FixedVersion::try_from(semver),
+            Err(ParseVersionError::PrereleaseTooLong)
+        );
+    }
+
+    #[test]
+    fn same_as_semver() {
+        for s in ["1.2.3", "2.0.0-beta.3", "1.0.0-x-y.7.z"] {
+            let version: FixedVersion = s.parse().unwrap();
+            let semver = semver::Version::parse(s).unwrap();
+            assert_eq!(semver::Version::from(version), semver);
+            assert_eq!(FixedVersion::try_from(semver), Ok(version));
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! The registration code must not depend on the prelude, because any crate in the dependency graph can enable `registry`.
+#![cfg(feature = "registry")]
+#![cfg_attr(feature = "specialization", feature(specialization))]
+#![allow(incomplete_features)]
+
+mod types {
+    use fixed_type_id::{fixed_type_id, FixedId, FixedTypeId, FixedVersion};
+
+    pub struct Plain;
+
+    fixed_type_id! {
+        #[version((0,1,0))]
+        tests::registry::Plain;
+    }
+
+    #[derive(FixedTypeId)]
+    #[fixed_type_id(name = "tests::registry::Derived", version = "0.2.0")]
+    pub struct Derived;
+}
+
+use fixed_type_id::{registry, FixedTypeId};
+
+#[test]
+fn register_without_prelude() {
+    let entry = registry::lookup(<types::Plain as FixedTypeId>::TYPE_ID).unwrap();
+    assert_eq!(entry.name, <types::Plain as FixedTypeId>::TYPE_NAME);
+    let entry = registry::lookup(<types::Derived as FixedTypeId>::TYPE_ID).unwrap();
+    assert_eq!(entry.name, <types::Derived as FixedTypeId>::TYPE_NAME);
+}
@@ -0,0 +1,324 @@
+//! Pin the ids of all the built-in implementations with the manifests in `tests/golden`.
+//!
+//! The manifests of the default id algorithm are in `tests/golden`, and the ones of the other algorithms
+//! in `tests/golden/xxh3` and `tests/golden/fnv1a`. Run the tests with `FIXED_TYPE_ID_BLESS=1`
+//! to update them after an intended change.
+//!
+//! With feature `erase_name` the names, and the ids of the generic types built from them, are different,
+//! so the manifests aren't checked.
+#![cfg_attr(feature = "specialization", feature(specialization))]
+#![cfg(all(feature = "std", not(feature = "erase_name")))]
+
+use fixed_type_id::manifest::{verify_stability, ManifestEntry};
+use fixed_type_id::manifest_entries;
+use fixed_type_id::prelude::*;
+
+/// The directory of the manifests of the selected id algorithm.
+const GOLDEN_DIR: &str = if cfg!(feature = "xxh3") {
+    "tests/golden/xxh3"
+} else if cfg!(feature = "fnv1a") {
+    "tests/golden/fnv1a"
+} else {
+    "tests/golden"
+};
+
+/// Check the entries against `{GOLDEN_DIR}/{name}.toml`, with feature `id128` only the low 64 bits are compared.
+///
+/// With feature `rustc_version`, the standard library types are versioned by the toolchain,
+/// so they are compared as `0.0.0`, their ids must stay the same.
+fn check(name: &str, entries: Vec<ManifestEntry>) {
+    let entries: Vec<_> = entries
+        .into_iter()
+        .map(|entry| ManifestEntry {
+            id: FixedId::from_u64(entry.id.as_u64()),
+            version: if entry.version == fixed_type_id::RUSTC_VERSION {
+                FixedVersion::new(0, 0, 0)
+            } else {
+                entry.version
+            },
+            ..entry
+        })
+        .collect();
+    let path = format!("{}/{GOLDEN_DIR}/{name}.toml", env!("CARGO_MANIFEST_DIR"));
+    if let Err(error) = verify_stability(&path, &entries) {
+        panic!("{path}: {error}");
+    }
+}
+
+#[test]
+fn stdlib() {
+    use core::convert::Infallible;
+    use core::marker::PhantomData;
+    use core::num::NonZero;
+    use core::ops::{Range, RangeFrom, RangeFull, RangeTo, RangeToInclusive};
+    use core::time::Duration;
+    use std::any::Any;
+    use std::collections::{BTreeMap, HashMap, VecDeque};
+
+    check(
+        "stdlib",
+        manifest_entries![
+            u8,
+            u16,
+            u32,
+            u64,
+            u128,
+            usize,
+            i8,
+            i16,
+            i32,
+            i64,
+            i128,
+            isize,
+            f32,
+            f64,
+            bool,
+            char,
+            String,
+            str,
+            (),
+            dyn Any,
+            &u8,
+            &str,
+            &mut u8,
+            &[u8],
+            &mut [u8],
+            [u8; 0],
+            [u8; 4],
+            [u8; 100],
+            Box<u8>,
+            Box<str>,
+            Vec<u8>,
+            VecDeque<u8>,
+            PhantomData<u8>,
+            HashMap<String, u32>,
+            BTreeMap<String, u32>,
+            Option<u8>,
+            Result<u8, String>,
+            Range<u32>,
+            RangeFrom<u32>,
+            RangeTo<u32>,
+            RangeToInclusive<u32>,
+            RangeFull,
+            NonZero<u32>,
+            Duration,
+            Infallible,
+            fn(u8) -> u16,
+            fn() -> u8,
+            fn(u8, u16) -> u32,
+            unsafe fn() -> u8,
+            extern "C" fn(u8) -> u8,
+            unsafe extern "C" fn(*const u8, usize) -> i32,
+            *const u8,
+            *mut u8,
+            (u8,),
+            (u8, u16),
+            (u8, u16, u32),
+            (u8, u16, u32, u64),
+            (u8, u16, u32, u64, u128),
+            (u8, u16, u32, u64, u128, usize),
+            (u8, u16, u32, u64, u128, usize, i8),
+            (u8, u16, u32, u64, u128, usize, i8, i16),
+            (u8, u16, u32, u64, u128, usize, i8, i16, i32),
+            (u8, u16, u32, u64, u128, usize, i8, i16, i32, i64),
+            (u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128),
+            (u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize),
+            (u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32),
+            (u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64),
+            (u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool),
+            (u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char),
+            Vec<(String, Option<Box<[u8; 16]>>)>,
+        ],
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+fn glam() {
+    use glam::*;
+
+    check(
+        "glam",
+        manifest_entries![
+            Vec2, Vec3, Vec3A, Vec4, Mat2, Mat3, Mat3A, Mat4, Quat, Affine2, Affine3A, DVec2,
+            DVec3, DVec4, DMat2, DMat3, DMat4, DQuat, DAffine2, DAffine3, I8Vec2, I8Vec3, I8Vec4,
+            U8Vec2, U8Vec3, U8Vec4, I16Vec2, I16Vec3, I16Vec4, U16Vec2, U16Vec3, U16Vec4, IVec2,
+            IVec3, IVec4, UVec2, UVec3, UVec4, I64Vec2, I64Vec3, I64Vec4, U64Vec2, U64Vec3,
+            U64Vec4, BVec2, BVec3, BVec4, EulerRot,
+        ],
+    );
+}
+
+#[cfg(feature = "macaw")]
+#[test]
+fn macaw() {
+    use macaw::*;
+
+    check(
+        "macaw",
+        manifest_entries![
+            Affine3A,
+            BoundingBox,
+            ColorRgba8,
+            Conformal3,
+            DAffine2,
+            DAffine3,
+            DMat2,
+            DMat3,
+            DMat4,
+            DVec2,
+            DVec3,
+            DVec4,
+            IVec2,
+            IVec3,
+            IVec4,
+            IsoTransform,
+            Mat2,
+            Mat3,
+            Mat3A,
+            Mat4,
+            MeshGen,
+            Plane3,
+            Quat,
+            Ray3,
+            UVec2,
+            UVec3,
+            UVec4,
+            Vec2,
+            Vec3,
+            Vec3A,
+            Vec4,
+            EulerRot,
+        ],
+    );
+}
+
+#[cfg(feature = "ultraviolet")]
+#[test]
+fn ultraviolet() {
+    use ultraviolet::*;
+
+    check(
+        "ultraviolet",
+        manifest_entries![
+            Bivec2,
+            Bivec2x4,
+            Bivec2x8,
+            Bivec3,
+            Bivec3x4,
+            Bivec3x8,
+            DBivec2,
+            DBivec2x2,
+            DBivec2x4,
+            DBivec3,
+            DBivec3x2,
+            DBivec3x4,
+            f32x4,
+            f32x8,
+            f64x2,
+            f64x4,
+            IVec2,
+            IVec3,
+            IVec4,
+            UVec2,
+            UVec3,
+            UVec4,
+            DMat2,
+            DMat2x2,
+            DMat2x4,
+            DMat3,
+            DMat3x2,
+            DMat3x4,
+            DMat4,
+            DMat4x2,
+            DMat4x4,
+            Mat2,
+            Mat2x4,
+            Mat2x8,
+            Mat3,
+            Mat3x4,
+            Mat3x8,
+            Mat4,
+            Mat4x4,
+            Mat4x8,
+            DRotor2,
+            DRotor2x2,
+            DRotor2x4,
+            DRotor3,
+            DRotor3x2,
+            DRotor3x4,
+            Rotor2,
+            Rotor2x4,
+            Rotor2x8,
+            Rotor3,
+            Rotor3x4,
+            Rotor3x8,
+            DIsometry2,
+            DIsometry2x2,
+            DIsometry2x4,
+            DIsometry3,
+            DIsometry3x2,
+            DIsometry3x4,
+            DSimilarity2,
+            DSimilarity2x2,
+            DSimilarity2x4,
+            DSimilarity3,
+            DSimilarity3x2,
+            DSimilarity3x4,
+            Isometry2,
+            Isometry2x4,
+            Isometry2x8,
+            Isometry3,
+            Isometry3x4,
+            Isometry3x8,
+            Similarity2,
+            Similarity2x4,
+            Similarity2x8,
+            Similarity3,
+            Similarity3x4,
+            Similarity3x8,
+            DVec2,
+            DVec2x2,
+            DVec2x4,
+            DVec3,
+            DVec3x2,
+            DVec3x4,
+            DVec4,
+            DVec4x2,
+            DVec4x4,
+            Vec2,
+            Vec2x4,
+            Vec2x8,
+            Vec3,
+            Vec3x4,
+            Vec3x8,
+            Vec4,
+            Vec4x4,
+            Vec4x8,
+        ],
+    );
+}
+
+#[cfg(feature = "uuid-1")]
+#[test]
+fn uuid() {
+    check("uuid", manifest_entries![uuid::Uuid]);
+}
+
+#[cfg(feature = "kollect")]
+#[test]
+fn kollect() {
+    use kollect::{LinearMap, LinearSet, OrderedMap, OrderedSet, UnorderedMap, UnorderedSet};
+
+    check(
+        "kollect",
+        manifest_entries![
+            LinearMap<String, u32>,
+            LinearSet<u32>,
+            OrderedMap<String, u32>,
+            OrderedSet<u32>,
+            UnorderedMap<String, u32>,
+            UnorderedSet<u32>,
+        ],
+    );
+}
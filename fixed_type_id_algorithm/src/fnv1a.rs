@@ -0,0 +1,18 @@
+//! A const implementation of 64-bit FNV-1a.
+
+/// The 64-bit FNV offset basis.
+pub const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+/// The 64-bit FNV prime.
+pub const FNV_PRIME: u64 = 0x100000001b3;
+
+/// FNV-1a a single byte stream, the seed is xor-ed into the offset basis.
+pub const fn fnv1a_seeded(data: &[u8], seed: u64) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS ^ seed;
+    let mut i = 0;
+    while i < data.len() {
+        hash ^= data[i] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
@@ -0,0 +1,263 @@
+//! The hashing algorithms used to generate `FixedId`s, shared by `fixed-type-id` and `fixed-type-id-macros`,
+//! so the ids generated in const context and by the proc macros can't diverge.
+//!
+//! An id is generated from the type name and, optionally, the version bytes:
+//!
+//! 1. hash the type name with the seed,
+//! 2. if there is a version, hash the 24 bytes of the version with the same seed,
+//!    then combine the two hashes with [`FixedIdAlgorithm::combine`].
+//!
+//! A 64-bit id uses [`FixedIdAlgorithm::SEED`], a 128-bit id adds the high 64 bits generated with [`ID128_HIGH_SEED`].
+//!
+//! Every algorithm is implemented in this crate as `const fn`, and is pinned, the ids it generates never change
+//! unless its [`FixedIdAlgorithm::ALGORITHM_VERSION`] is bumped.
+//!
+//! ```rust
+//! use fixed_type_id_algorithm::{version_to_bytes, FixedIdAlgorithm, Fnv1a, Rapidhash};
+//!
+//! let version = version_to_bytes(0, 1, 0);
+//! let id = Rapidhash::hash_name_version(b"MyType", Some(&version), Rapidhash::SEED);
+//! assert_eq!(<Rapidhash as FixedIdAlgorithm>::hash_name_version(b"MyType", Some(&version), Rapidhash::SEED), id);
+//! assert_eq!(Fnv1a::hash_seeded(b"a", Fnv1a::SEED), 0xaf63dc4c8601ec8c);
+//! ```
+#![no_std]
+#![deny(missing_docs)]
+
+pub mod fnv1a;
+pub mod rapidhash;
+#[cfg(feature = "xxh3")]
+pub mod xxh3;
+
+/// The seed used to hash the high 64 bits of a 128-bit id, the low 64 bits are the same as the 64-bit id.
+pub const ID128_HIGH_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// The max length of the pre-release of a `FixedVersion`, in bytes, the macros reject longer pre-releases.
+pub const MAX_PRERELEASE_LEN: usize = 32;
+
+/// Get the bytes of a version, each part is written as a little endian `u64`.
+pub const fn version_to_bytes(major: u64, minor: u64, patch: u64) -> [u8; 24] {
+    let mut bytes = [0u8; 24];
+    let parts = [major, minor, patch];
+    let mut i = 0;
+    while i < 24 {
+        bytes[i] = parts[i / 8].to_le_bytes()[i % 8];
+        i += 1;
+    }
+    bytes
+}
+
+/// An algorithm to generate a `FixedId` from a type name and version.
+///
+/// Each implementor also has inherent `const fn`s with the same names, which are used in const context.
+pub trait FixedIdAlgorithm {
+    /// The name of the algorithm.
+    const NAME: &'static str;
+    /// The version of the algorithm, bumped whenever the ids it generates change.
+    const ALGORITHM_VERSION: u32;
+    /// The seed of a 64-bit id, and of the low 64 bits of a 128-bit id.
+    const SEED: u64;
+
+    /// Hash the bytes with a seed.
+    fn hash_seeded(bytes: &[u8], seed: u64) -> u64;
+
+    /// Combine the hash of the type name and the hash of the version.
+    fn combine(name_hash: u64, version_hash: u64, seed: u64) -> u64;
+
+    /// Hash the type name, then combine it with the hash of the version bytes, if any.
+    fn hash_name_version(name: &[u8], version: Option<&[u8; 24]>, seed: u64) -> u64 {
+        let name_hash = Self::hash_seeded(name, seed);
+        match version {
+            None => name_hash,
+            Some(bytes) => Self::combine(name_hash, Self::hash_seeded(bytes, seed), seed),
+        }
+    }
+}
+
+/// Implement [`FixedIdAlgorithm`] by the inherent `const fn hash_seeded` and `const fn combine`,
+/// and add the inherent `const fn hash_name_version`.
+macro_rules! impl_algorithm {
+    ($ty:ty, name: $name:literal, version: $version:literal, seed: $seed:expr) => {
+        impl $ty {
+            /// Hash the type name, then combine it with the hash of the version bytes, if any.
+            pub const fn hash_name_version(
+                name: &[u8],
+                version: Option<&[u8; 24]>,
+                seed: u64,
+            ) -> u64 {
+                let name_hash = Self::hash_seeded(name, seed);
+                match version {
+                    None => name_hash,
+                    Some(bytes) => Self::combine(name_hash, Self::hash_seeded(bytes, seed), seed),
+                }
+            }
+        }
+
+        impl $crate::FixedIdAlgorithm for $ty {
+            const NAME: &'static str = $name;
+            const ALGORITHM_VERSION: u32 = $version;
+            const SEED: u64 = $seed;
+
+            #[inline]
+            fn hash_seeded(bytes: &[u8], seed: u64) -> u64 {
+                <$ty>::hash_seeded(bytes, seed)
+            }
+
+            #[inline]
+            fn combine(name_hash: u64, version_hash: u64, seed: u64) -> u64 {
+                <$ty>::combine(name_hash, version_hash, seed)
+            }
+        }
+    };
+}
+
+/// Concat the little endian bytes of two hashes, used to combine them by algorithms without a mixing function.
+const fn pair_to_bytes(name_hash: u64, version_hash: u64) -> [u8; 16] {
+    let mut bytes = [0u8; 16];
+    let (name, version) = (name_hash.to_le_bytes(), version_hash.to_le_bytes());
+    let mut i = 0;
+    while i < 8 {
+        bytes[i] = name[i];
+        bytes[i + 8] = version[i];
+        i += 1;
+    }
+    bytes
+}
+
+/// rapidhash v1, combined with `rapid_mix`, the default algorithm.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rapidhash;
+
+impl Rapidhash {
+    /// Hash the bytes with a seed.
+    pub const fn hash_seeded(bytes: &[u8], seed: u64) -> u64 {
+        rapidhash::rapidhash_seeded(bytes, seed)
+    }
+
+    /// Combine the two hashes with `rapid_mix`, the seed is unused.
+    pub const fn combine(name_hash: u64, version_hash: u64, _seed: u64) -> u64 {
+        rapidhash::rapid_mix(name_hash, version_hash)
+    }
+}
+
+impl_algorithm!(Rapidhash, name: "rapidhash", version: 1, seed: rapidhash::RAPID_SEED);
+
+/// 64-bit FNV-1a, the seed is xor-ed into the offset basis, so the default seed `0` gives the standard FNV-1a.
+///
+/// The two hashes are combined by hashing their 16 little endian bytes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fnv1a;
+
+impl Fnv1a {
+    /// Hash the bytes with a seed.
+    pub const fn hash_seeded(bytes: &[u8], seed: u64) -> u64 {
+        fnv1a::fnv1a_seeded(bytes, seed)
+    }
+
+    /// Combine the two hashes by hashing their 16 little endian bytes.
+    pub const fn combine(name_hash: u64, version_hash: u64, seed: u64) -> u64 {
+        fnv1a::fnv1a_seeded(&pair_to_bytes(name_hash, version_hash), seed)
+    }
+}
+
+impl_algorithm!(Fnv1a, name: "fnv1a", version: 1, seed: 0);
+
+/// 64-bit XXH3, the default seed `0` gives the standard `XXH3_64bits`.
+///
+/// The two hashes are combined by hashing their 16 little endian bytes.
+#[cfg(feature = "xxh3")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Xxh3;
+
+#[cfg(feature = "xxh3")]
+impl Xxh3 {
+    /// Hash the bytes with a seed.
+    pub const fn hash_seeded(bytes: &[u8], seed: u64) -> u64 {
+        xxh3::xxh3_64_seeded(bytes, seed)
+    }
+
+    /// Combine the two hashes by hashing their 16 little endian bytes.
+    pub const fn combine(name_hash: u64, version_hash: u64, seed: u64) -> u64 {
+        xxh3::xxh3_64_seeded(&pair_to_bytes(name_hash, version_hash), seed)
+    }
+}
+
+#[cfg(feature = "xxh3")]
+impl_algorithm!(Xxh3, name: "xxh3", version: 1, seed: 0);
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn inputs() -> impl Iterator<Item = &'static [u8]> {
+        const DATA: &[u8; 512] = &{
+            let mut data = [0u8; 512];
+            let mut i = 0;
+            while i < data.len() {
+                data[i] = (i * 31 + 7) as u8;
+                i += 1;
+            }
+            data
+        };
+        (0..DATA.len()).map(|len| &DATA[..len])
+    }
+
+    #[test]
+    fn vendored_rapidhash_matches_upstream() {
+        for input in inputs() {
+            for seed in [crate::rapidhash::RAPID_SEED, ID128_HIGH_SEED, 0] {
+                assert_eq!(
+                    crate::rapidhash::rapidhash_seeded(input, seed),
+                    ::rapidhash::rapidhash_seeded(input, seed),
+                    "length {}",
+                    input.len()
+                );
+            }
+        }
+        assert_eq!(
+            crate::rapidhash::rapidhash(b"hello world"),
+            17498481775468162579
+        );
+    }
+
+    #[test]
+    fn fnv1a_vectors() {
+        assert_eq!(fnv1a::fnv1a_seeded(b"", 0), 0xcbf29ce484222325);
+        assert_eq!(fnv1a::fnv1a_seeded(b"a", 0), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a::fnv1a_seeded(b"foobar", 0), 0x85944171f73967e8);
+    }
+
+    #[cfg(feature = "xxh3")]
+    #[test]
+    fn xxh3_vectors() {
+        assert_eq!(xxh3::xxh3_64_seeded(b"", 0), 0x2d06800538d394c2);
+    }
+
+    #[test]
+    fn const_same_as_trait() {
+        fn check<A: FixedIdAlgorithm>(hash: fn(&[u8], Option<&[u8; 24]>, u64) -> u64) {
+            let version = version_to_bytes(1, 2, 3);
+            for input in inputs() {
+                assert_eq!(
+                    A::hash_name_version(input, Some(&version), A::SEED),
+                    hash(input, Some(&version), A::SEED)
+                );
+                assert_eq!(
+                    A::hash_name_version(input, None, A::SEED),
+                    A::hash_seeded(input, A::SEED)
+                );
+            }
+        }
+        check::<Rapidhash>(Rapidhash::hash_name_version);
+        check::<Fnv1a>(Fnv1a::hash_name_version);
+        #[cfg(feature = "xxh3")]
+        check::<Xxh3>(Xxh3::hash_name_version);
+    }
+
+    #[test]
+    fn version_bytes() {
+        let bytes = version_to_bytes(1, 2, u64::MAX);
+        assert_eq!(bytes[..8], 1u64.to_le_bytes());
+        assert_eq!(bytes[8..16], 2u64.to_le_bytes());
+        assert_eq!(bytes[16..], u64::MAX.to_le_bytes());
+    }
+}
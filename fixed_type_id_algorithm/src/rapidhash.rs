@@ -0,0 +1,166 @@
+//! A const implementation of rapidhash, vendored from the `rapidhash` crate v1.1.0.
+//!
+//! Copyright (c) 2024 Liam Gray, licensed under MIT. The code is copied instead of depended on,
+//! so upgrading `rapidhash` can never change the ids. Don't modify the hashing logic,
+//! any change must come with a new [`FixedIdAlgorithm::ALGORITHM_VERSION`](crate::FixedIdAlgorithm::ALGORITHM_VERSION).
+
+/// The rapidhash default seed.
+pub const RAPID_SEED: u64 = 0xbdd89aa982704029;
+const RAPID_SECRET: [u64; 3] = [0x2d358dccaa6c78a5, 0x8bb84b93962eacc9, 0x4b33a62ed433d4a3];
+
+/// Rapidhash a single byte stream with [`RAPID_SEED`].
+#[inline]
+pub const fn rapidhash(data: &[u8]) -> u64 {
+    rapidhash_seeded(data, RAPID_SEED)
+}
+
+/// Rapidhash a single byte stream with a custom seed.
+#[inline]
+pub const fn rapidhash_seeded(data: &[u8], mut seed: u64) -> u64 {
+    seed = rapidhash_seed(seed, data.len() as u64);
+    let (a, b, _) = rapidhash_core(0, 0, seed, data);
+    rapidhash_finish(a, b, data.len() as u64)
+}
+
+/// Multiply two `u64` into a `u128`, returns the low and high 64 bits.
+#[inline(always)]
+pub const fn rapid_mum(a: u64, b: u64) -> (u64, u64) {
+    let r = a as u128 * b as u128;
+    (r as u64, (r >> 64) as u64)
+}
+
+/// Multiply two `u64` into a `u128`, returns the xor of the low and high 64 bits.
+#[inline(always)]
+pub const fn rapid_mix(a: u64, b: u64) -> u64 {
+    let (a, b) = rapid_mum(a, b);
+    a ^ b
+}
+
+#[inline(always)]
+const fn rapidhash_seed(seed: u64, len: u64) -> u64 {
+    seed ^ rapid_mix(seed ^ RAPID_SECRET[0], RAPID_SECRET[1]) ^ len
+}
+
+#[inline(always)]
+const fn rapidhash_core(mut a: u64, mut b: u64, mut seed: u64, data: &[u8]) -> (u64, u64, u64) {
+    if data.len() <= 16 {
+        if data.len() >= 8 {
+            // len is 8..=16
+            let plast = data.len() - 4;
+            let delta = 4;
+            a ^= read_u32_combined(data, 0, plast);
+            b ^= read_u32_combined(data, delta, plast - delta);
+        } else if data.len() >= 4 {
+            // len is 4..8
+            let plast = data.len() - 4;
+            a ^= read_u32_combined(data, 0, plast);
+            b ^= read_u32_combined(data, 0, plast);
+        } else if !data.is_empty() {
+            // len is 1..=3
+            let len = data.len();
+            a ^= ((data[0] as u64) << 56) | ((data[len >> 1] as u64) << 32) | data[len - 1] as u64;
+        }
+    } else {
+        let mut slice = data;
+
+        let mut see1 = seed;
+        let mut see2 = seed;
+        while slice.len() >= 96 {
+            seed = rapid_mix(
+                read_u64(slice, 0) ^ RAPID_SECRET[0],
+                read_u64(slice, 8) ^ seed,
+            );
+            see1 = rapid_mix(
+                read_u64(slice, 16) ^ RAPID_SECRET[1],
+                read_u64(slice, 24) ^ see1,
+            );
+            see2 = rapid_mix(
+                read_u64(slice, 32) ^ RAPID_SECRET[2],
+                read_u64(slice, 40) ^ see2,
+            );
+            seed = rapid_mix(
+                read_u64(slice, 48) ^ RAPID_SECRET[0],
+                read_u64(slice, 56) ^ seed,
+            );
+            see1 = rapid_mix(
+                read_u64(slice, 64) ^ RAPID_SECRET[1],
+                read_u64(slice, 72) ^ see1,
+            );
+            see2 = rapid_mix(
+                read_u64(slice, 80) ^ RAPID_SECRET[2],
+                read_u64(slice, 88) ^ see2,
+            );
+            let (_, split) = slice.split_at(96);
+            slice = split;
+        }
+        if slice.len() >= 48 {
+            seed = rapid_mix(
+                read_u64(slice, 0) ^ RAPID_SECRET[0],
+                read_u64(slice, 8) ^ seed,
+            );
+            see1 = rapid_mix(
+                read_u64(slice, 16) ^ RAPID_SECRET[1],
+                read_u64(slice, 24) ^ see1,
+            );
+            see2 = rapid_mix(
+                read_u64(slice, 32) ^ RAPID_SECRET[2],
+                read_u64(slice, 40) ^ see2,
+            );
+            let (_, split) = slice.split_at(48);
+            slice = split;
+        }
+        seed ^= see1 ^ see2;
+
+        if slice.len() > 16 {
+            seed = rapid_mix(
+                read_u64(slice, 0) ^ RAPID_SECRET[2],
+                read_u64(slice, 8) ^ seed ^ RAPID_SECRET[1],
+            );
+            if slice.len() > 32 {
+                seed = rapid_mix(
+                    read_u64(slice, 16) ^ RAPID_SECRET[2],
+                    read_u64(slice, 24) ^ seed,
+                );
+            }
+        }
+
+        a ^= read_u64(data, data.len() - 16);
+        b ^= read_u64(data, data.len() - 8);
+    }
+
+    a ^= RAPID_SECRET[1];
+    b ^= seed;
+
+    let (a, b) = rapid_mum(a, b);
+    (a, b, seed)
+}
+
+#[inline(always)]
+const fn rapidhash_finish(a: u64, b: u64, len: u64) -> u64 {
+    rapid_mix(a ^ RAPID_SECRET[0] ^ len, b ^ RAPID_SECRET[1])
+}
+
+/// Read a little endian `u64` at `offset`, const-friendly and memory-safe.
+#[inline(always)]
+const fn read_u64(slice: &[u8], offset: usize) -> u64 {
+    match slice.split_at(offset).1.first_chunk::<8>() {
+        Some(buf) => u64::from_le_bytes(*buf),
+        None => panic!("read_u64: slice too short"),
+    }
+}
+
+/// Read a little endian `u32` at `offset`, const-friendly and memory-safe.
+#[inline(always)]
+const fn read_u32(slice: &[u8], offset: usize) -> u32 {
+    match slice.split_at(offset).1.first_chunk::<4>() {
+        Some(buf) => u32::from_le_bytes(*buf),
+        None => panic!("read_u32: slice too short"),
+    }
+}
+
+#[inline(always)]
+const fn read_u32_combined(slice: &[u8], offset_top: usize, offset_bot: usize) -> u64 {
+    let top = read_u32(slice, offset_top) as u64;
+    let bot = read_u32(slice, offset_bot) as u64;
+    (top << 32) | bot
+}
@@ -0,0 +1,9 @@
+//! 64-bit XXH3 in const context, provided by `xxhash-rust`.
+//!
+//! XXH3 is a stable specification since xxHash v0.8.0, so the ids can't change with `xxhash-rust` upgrades.
+
+/// XXH3 64-bit a single byte stream with a seed.
+#[inline]
+pub const fn xxh3_64_seeded(data: &[u8], seed: u64) -> u64 {
+    xxhash_rust::const_xxh3::xxh3_64_with_seed(data, seed)
+}
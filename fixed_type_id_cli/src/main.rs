@@ -0,0 +1,243 @@
+//! `fixed-type-id`, compute and inspect fixed type ids and their manifests without writing Rust.
+//!
+//! ```text
+//! fixed-type-id hash <name> [--version x.y.z] [--omit-version]
+//! fixed-type-id show <id> --manifest ids.toml [--manifest other.toml]
+//! fixed-type-id diff old.toml new.toml
+//! fixed-type-id check ids.toml [other.toml]
+//! ```
+//!
+//! The ids are computed with the same algorithm and width as the `fixed-type-id` crate built with the same features,
+//! so build the tool with `--features id128`, `xxh3` or `fnv1a` if your crate uses them.
+//!
+//! `diff`, `check` and a `show` without any match exit with code `1`, so they can be used in CI.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use fixed_type_id::manifest::{self, Manifest, ManifestEntry};
+use fixed_type_id::{FixedId, FixedVersion, RawFixedId};
+
+#[derive(Debug, Parser)]
+#[command(name = "fixed-type-id", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Compute the id of a type name, the same as `FixedId::from_type_name`.
+    Hash {
+        /// The type name, like `my_crate::MyType<u8>`.
+        name: String,
+        /// The version hashed into the id, `0.0.0` by default, like a type without a version.
+        #[arg(long, value_parser = manifest::parse_version, conflicts_with = "omit_version")]
+        version: Option<FixedVersion>,
+        /// Don't hash the version into the id, like `omit_version_hash`.
+        #[arg(long)]
+        omit_version: bool,
+    },
+    /// Find the types of an id in manifests.
+    Show {
+        /// The id, as hex like `0x1f` or as decimal.
+        #[arg(value_parser = parse_id_arg)]
+        id: FixedId,
+        /// The manifests to search, can be repeated.
+        #[arg(long = "manifest", required = true)]
+        manifests: Vec<PathBuf>,
+    },
+    /// List the added, removed and changed ids between two manifests.
+    Diff {
+        /// The old manifest.
+        old: PathBuf,
+        /// The new manifest.
+        new: PathBuf,
+    },
+    /// Report the types with the same id in manifests, checked together.
+    Check {
+        /// The manifests to check.
+        #[arg(required = true)]
+        manifests: Vec<PathBuf>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut out = String::new();
+    let result = run(cli.command, &mut out);
+    print!("{out}");
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Run a command, write its output to `out`, returns `false` if the command found a difference,
+/// a collision or nothing.
+fn run(command: Command, out: &mut String) -> Result<bool, String> {
+    match command {
+        Command::Hash {
+            name,
+            version,
+            omit_version,
+        } => {
+            let version = (!omit_version).then(|| version.unwrap_or(FixedVersion::new(0, 0, 0)));
+            out.push_str(&format!("{}\n", manifest::format_id(hash(name, version))));
+            Ok(true)
+        }
+        Command::Show { id, manifests } => {
+            let mut found = false;
+            for path in manifests {
+                let manifest = load(&path)?;
+                for entry in manifest.find_id(id) {
+                    out.push_str(&format!("{}: {entry}\n", path.display()));
+                    found = true;
+                }
+            }
+            if !found {
+                out.push_str(&format!("no type with id {}\n", manifest::format_id(id)));
+            }
+            Ok(found)
+        }
+        Command::Diff { old, new } => {
+            let diff = load(&old)?.diff(&load(&new)?);
+            out.push_str(&diff.to_string());
+            Ok(diff.is_empty())
+        }
+        Command::Check { manifests } => {
+            let manifests = manifests
+                .into_iter()
+                .map(|path| Ok((load(&path)?, path)))
+                .collect::<Result<Vec<_>, String>>()?;
+            let collisions = collisions(&manifests);
+            for (id, entries) in &collisions {
+                out.push_str(&format!("collision of {}:\n", manifest::format_id(*id)));
+                for (path, entry) in entries {
+                    out.push_str(&format!("  {}: {entry}\n", path.display()));
+                }
+            }
+            if collisions.is_empty() {
+                let types: usize = manifests.iter().map(|(manifest, _)| manifest.len()).sum();
+                out.push_str(&format!("no collision in {types} types\n"));
+            }
+            Ok(collisions.is_empty())
+        }
+    }
+}
+
+/// Compute the id with [`FixedId::from_type_name`], which needs a `&'static str`.
+fn hash(name: String, version: Option<FixedVersion>) -> FixedId {
+    FixedId::from_type_name(name.leak(), version)
+}
+
+/// Group the entries of all manifests by id, and keep the ids shared by different type names.
+fn collisions(
+    manifests: &[(Manifest, PathBuf)],
+) -> BTreeMap<FixedId, Vec<(&Path, &ManifestEntry)>> {
+    let mut by_id: BTreeMap<FixedId, Vec<(&Path, &ManifestEntry)>> = BTreeMap::new();
+    for (manifest, path) in manifests {
+        for entry in manifest.iter() {
+            by_id
+                .entry(entry.id)
+                .or_default()
+                .push((path.as_path(), entry));
+        }
+    }
+    by_id.retain(|_, entries| {
+        entries
+            .iter()
+            .any(|(_, entry)| entry.name != entries[0].1.name)
+    });
+    by_id
+}
+
+fn load(path: &Path) -> Result<Manifest, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|error| format!("can't read `{}`: {error}", path.display()))?;
+    content
+        .parse()
+        .map_err(|error| format!("`{}`: {error}", path.display()))
+}
+
+fn parse_id_arg(s: &str) -> Result<FixedId, String> {
+    if s.starts_with("0x") {
+        manifest::parse_id(s)
+    } else {
+        s.parse::<RawFixedId>().map(FixedId).map_err(|_| {
+            format!("invalid id `{s}`, expected a hex integer like `0x1f` or a decimal integer")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use fixed_type_id::FixedTypeId;
+
+    fn entry(name: &str, id: RawFixedId) -> ManifestEntry {
+        ManifestEntry {
+            name: name.to_string(),
+            version: FixedVersion::new(0, 1, 0),
+            id: FixedId(id),
+            location: None,
+        }
+    }
+
+    #[test]
+    fn hash_same_as_type_id() {
+        assert_eq!(
+            hash("u8".to_string(), Some(<u8 as FixedTypeId>::TYPE_VERSION)),
+            <u8 as FixedTypeId>::TYPE_ID
+        );
+        assert_eq!(
+            hash("u8".to_string(), None),
+            FixedId::from_type_name("u8", None)
+        );
+        let mut out = String::new();
+        let command = Cli::parse_from(["fixed-type-id", "hash", "u8"]).command;
+        assert!(run(command, &mut out).unwrap());
+        assert_eq!(
+            out,
+            format!("{}\n", manifest::format_id(<u8 as FixedTypeId>::TYPE_ID))
+        );
+        assert!(Cli::try_parse_from([
+            "fixed-type-id",
+            "hash",
+            "u8",
+            "--version",
+            "1.0.0",
+            "--omit-version"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_ids() {
+        assert_eq!(parse_id_arg("0x1f"), Ok(FixedId(31)));
+        assert_eq!(parse_id_arg("31"), Ok(FixedId(31)));
+        assert!(parse_id_arg("0xzz").is_err());
+        assert!(parse_id_arg("-1").is_err());
+    }
+
+    #[test]
+    fn find_collisions() {
+        let a = Manifest::new([entry("a", 1), entry("b", 2)]);
+        let b = Manifest::new([entry("a", 1), entry("c", 2), entry("d", 3)]);
+        let manifests = [(a, PathBuf::from("a.toml")), (b, PathBuf::from("b.toml"))];
+        let collisions = collisions(&manifests);
+        // the same type in two manifests isn't a collision
+        assert_eq!(collisions.keys().collect::<Vec<_>>(), [&FixedId(2)]);
+        let names: Vec<_> = collisions[&FixedId(2)]
+            .iter()
+            .map(|(path, entry)| format!("{}: {}", path.display(), entry.name))
+            .collect();
+        assert_eq!(names, ["a.toml: b", "b.toml: c"]);
+    }
+}
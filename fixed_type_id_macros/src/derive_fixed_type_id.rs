@@ -0,0 +1,234 @@
+use fixed_type_id_algorithm::rapidhash::rapidhash;
+use proc_macro::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, GenericParam, LitStr,
+    Result,
+};
+
+use crate::fixed_type_id::{
+    const_param_name, parse_crate_version, parse_version_str, register_type, Version,
+};
+
+/// Options parsed from `#[fixed_type_id(...)]` attributes on the derive input.
+#[derive(Default)]
+struct DeriveAttributes {
+    version: Version,
+    name: Option<LitStr>,
+    omit_version_hash: bool,
+    equal_to: Option<syn::Type>,
+    layout: bool,
+}
+
+impl DeriveAttributes {
+    fn from_attrs(attrs: &[syn::Attribute]) -> Result<Self> {
+        let mut options = DeriveAttributes::default();
+        for attr in attrs {
+            if !attr.path().is_ident("fixed_type_id") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("version") {
+                    let value = meta.value()?;
+                    options.version = if value.peek(syn::Token![crate]) {
+                        parse_crate_version(&value.parse()?)?
+                    } else {
+                        parse_version_str(&value.parse::<LitStr>()?)?
+                    };
+                } else if meta.path.is_ident("name") {
+                    options.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("omit_version_hash") {
+                    options.omit_version_hash = true;
+                } else if meta.path.is_ident("equal_to") {
+                    options.equal_to = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("layout") {
+                    options.layout = true;
+                } else {
+                    return Err(meta.error(
+                        "unknown `fixed_type_id` option, expected one of `version`, `name`, `omit_version_hash`, `equal_to`, `layout`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+pub fn derive_fixed_type_id_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let options = DeriveAttributes::from_attrs(&input.attrs)?;
+    let ident = &input.ident;
+    let version = &options.version;
+
+    // the name is either provided by the user, or the full path of the type
+    let name = match &options.name {
+        Some(name) => quote! { #name },
+        None => {
+            let ident_str = ident.to_string();
+            quote! { ::core::concat!(::core::module_path!(), "::", #ident_str) }
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    let mut type_params = Vec::new();
+    // the name segments of type and const parameters, in declaration order
+    let mut param_names = Vec::new();
+    for param in &input.generics.params {
+        match param {
+            GenericParam::Type(type_param) => {
+                let param = &type_param.ident;
+                param_names.push(quote! { <#param as self::FixedTypeId>::TYPE_NAME });
+                type_params.push(param.clone());
+            }
+            GenericParam::Lifetime(_) => {}
+            GenericParam::Const(const_param) => {
+                param_names.push(const_param_name(const_param)?);
+            }
+        }
+    }
+    {
+        let where_clause = generics.make_where_clause();
+        for param in &type_params {
+            where_clause
+                .predicates
+                .push(parse_quote! { #param: self::FixedTypeId });
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let layout_hash = if options.layout {
+        layout_hash(&input.data)
+    } else {
+        quote! {}
+    };
+
+    let implementation = if param_names.is_empty() {
+        // concrete type, the name is known at compile time
+        let type_name = match &options.name {
+            Some(name) if cfg!(feature = "erase_name") => {
+                let erased = format!("0x{:x}", rapidhash(name.value().as_bytes()));
+                quote! { #erased }
+            }
+            // the default name is only known after `module_path!()` expands, so it's erased in const context
+            None if cfg!(feature = "erase_name") => quote! {
+                {
+                    const ERASED: (
+                        [u8; self::__fixed_type_id_const_str::ERASED_NAME_LEN],
+                        usize,
+                    ) = self::__fixed_type_id_const_str::erase_name(#name);
+                    self::__fixed_type_id_const_str::bytes_to_str(&ERASED.0, ERASED.1)
+                }
+            },
+            _ => name.clone(),
+        };
+        let hashed_name = name;
+        let type_id = if let Some(equal_to) = &options.equal_to {
+            quote! { <#equal_to as self::FixedTypeId>::TYPE_ID }
+        } else if options.omit_version_hash {
+            quote! { self::FixedId::from_type_name(#hashed_name, None) }
+        } else {
+            quote! { self::FixedId::from_type_name(#hashed_name, Some(Self::TYPE_VERSION)) }
+        };
+        // types with only lifetime parameters can't be registered
+        let registration = if generics.params.is_empty() {
+            register_type(
+                &quote! { #ident },
+                options
+                    .equal_to
+                    .as_ref()
+                    .map(|target| target as &dyn ToTokens),
+            )
+        } else {
+            quote! {}
+        };
+        quote! {
+            impl #impl_generics self::FixedTypeId for #ident #ty_generics #where_clause {
+                const TYPE_NAME: &'static str = #type_name;
+                const TYPE_ID: self::FixedId = #type_id;
+                const TYPE_VERSION: self::FixedVersion = #version;
+                #layout_hash
+            }
+
+            #registration
+        }
+    } else {
+        // generic type, use ConstTypeName to build the name in const context
+        let mut raw_slice = Vec::new();
+        for (index, param_name) in param_names.iter().enumerate() {
+            if index != 0 {
+                raw_slice.push(quote! { "," });
+            }
+            raw_slice.push(param_name.clone());
+        }
+        let type_id = if let Some(equal_to) = &options.equal_to {
+            quote! { <#equal_to as self::FixedTypeId>::TYPE_ID }
+        } else if options.omit_version_hash {
+            quote! { self::FixedId::from_type_name(Self::TYPE_NAME, None) }
+        } else {
+            quote! { self::FixedId::from_type_name(Self::TYPE_NAME, Some(Self::TYPE_VERSION)) }
+        };
+        quote! {
+            impl #impl_generics self::ConstTypeName for #ident #ty_generics #where_clause {
+                const RAW_SLICE: &'static [&'static str] = &[
+                    #name,
+                    "<",
+                    #(#raw_slice,)*
+                    ">"
+                ];
+            }
+
+            impl #impl_generics self::FixedTypeId for #ident #ty_generics #where_clause {
+                const TYPE_NAME: &'static str = <Self as self::ConstTypeName>::TYPE_NAME_STR;
+                const TYPE_ID: self::FixedId = #type_id;
+                const TYPE_VERSION: self::FixedVersion = #version;
+                #layout_hash
+            }
+        }
+    };
+
+    Ok(implementation)
+}
+
+/// Generate `TYPE_LAYOUT_HASH` from the fields of a struct or union, or the variants and their fields of an enum.
+fn layout_hash(data: &Data) -> proc_macro2::TokenStream {
+    let mut parts = Vec::new();
+    match data {
+        Data::Struct(data) => layout_fields(&data.fields, &mut parts),
+        Data::Enum(data) => {
+            for variant in &data.variants {
+                let name = variant.ident.to_string();
+                parts.push(quote! { .variant(#name) });
+                layout_fields(&variant.fields, &mut parts);
+            }
+        }
+        Data::Union(data) => layout_fields(&data.fields.named, &mut parts),
+    }
+    quote! {
+        const TYPE_LAYOUT_HASH: u64 = self::__fixed_type_id_layout::LayoutHasher::of::<Self>()
+            #(#parts)*
+            .finish();
+    }
+}
+
+/// Hash the fields in order, a tuple field is named by its index.
+fn layout_fields<'a>(
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+    parts: &mut Vec<proc_macro2::TokenStream>,
+) {
+    for (index, field) in fields.into_iter().enumerate() {
+        let name = field
+            .ident
+            .as_ref()
+            .map_or_else(|| index.to_string(), |ident| ident.to_string());
+        let ty = &field.ty;
+        // point a missing `FixedTypeId` implementation to the field type
+        parts.push(quote_spanned! { ty.span()=> .field::<#ty>(#name) });
+    }
+}
@@ -1,52 +1,291 @@
-use core::panic;
+use crate::lockfile::{LockEntry, Lockfile};
+#[cfg(feature = "id128")]
+use fixed_type_id_algorithm::ID128_HIGH_SEED;
+use fixed_type_id_algorithm::{
+    rapidhash::rapidhash, version_to_bytes, FixedIdAlgorithm, MAX_PRERELEASE_LEN,
+};
 use proc_macro::TokenStream;
 use quote::{quote, ToTokens};
 use rand::prelude::*;
-use rapidhash::rapidhash;
-use std::fs::File;
 use syn::{
     parenthesized,
-    parse::{Parse, ParseStream},
-    parse_macro_input, Attribute, Expr, GenericArgument, Lit, PathArguments, Result, Token,
-    TraitBound, TypeParamBound,
+    parse::{discouraged::Speculative, Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Expr, GenericArgument, Lit, PathArguments, Result, Token, TraitBound,
+    TypeParamBound,
 };
 
-/// Copy from [`rapidhash`]
-#[inline(always)]
-const fn rapid_mum(a: u64, b: u64) -> (u64, u64) {
-    let r = a as u128 * b as u128;
-    (r as u64, (r >> 64) as u64)
+/// The id algorithm, must be the same as `fixed_type_id::IdAlgorithm`.
+#[cfg(not(any(feature = "xxh3", feature = "fnv1a")))]
+type IdAlgorithm = fixed_type_id_algorithm::Rapidhash;
+
+/// The id algorithm, must be the same as `fixed_type_id::IdAlgorithm`.
+#[cfg(feature = "xxh3")]
+type IdAlgorithm = fixed_type_id_algorithm::Xxh3;
+
+/// The id algorithm, must be the same as `fixed_type_id::IdAlgorithm`.
+#[cfg(all(feature = "fnv1a", not(feature = "xxh3")))]
+type IdAlgorithm = fixed_type_id_algorithm::Fnv1a;
+
+/// The inner integer of `FixedId`, must be the same as `fixed_type_id::RawFixedId`.
+#[cfg(not(feature = "id128"))]
+pub(crate) type RawFixedId = u64;
+
+/// The inner integer of `FixedId`, must be the same as `fixed_type_id::RawFixedId`.
+#[cfg(feature = "id128")]
+pub(crate) type RawFixedId = u128;
+
+/// A version given to the macros, the same as `fixed_type_id::FixedVersion`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// The pre-release, empty for a release.
+    pub pre: String,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+            pre: String::new(),
+        }
+    }
+}
+
+/// Formats the version as `major.minor.patch[-pre]`, like `fixed_type_id::FixedVersion`.
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre)?;
+        }
+        Ok(())
+    }
+}
+
+/// Expands to the `self::FixedVersion` in const context.
+impl ToTokens for Version {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Version {
+            major,
+            minor,
+            patch,
+            pre,
+        } = self;
+        tokens.extend(if pre.is_empty() {
+            quote! { self::FixedVersion::new(#major, #minor, #patch) }
+        } else {
+            quote! { self::FixedVersion::new_pre(#major, #minor, #patch, #pre) }
+        });
+    }
 }
 
-/// Copy from [`rapidhash`]
-#[inline(always)]
-const fn rapid_mix(a: u64, b: u64) -> u64 {
-    let (a, b) = rapid_mum(a, b);
-    a ^ b
+/// Hash the name and the version the same way as `fixed_type_id::FixedId::from_type_name`.
+fn hash_name_version(name: &str, version: Option<&Version>) -> RawFixedId {
+    let low = hash_name_version_seeded(name, version, IdAlgorithm::SEED);
+    #[cfg(feature = "id128")]
+    {
+        let high = hash_name_version_seeded(name, version, ID128_HIGH_SEED);
+        ((high as u128) << 64) | low as u128
+    }
+    #[cfg(not(feature = "id128"))]
+    low
 }
 
-fn version_to_hash(version: &(u64, u64, u64)) -> u64 {
-    // make a version tuple to a [u8; 24]
-    let mut bytes = [0u8; 24];
-    bytes[0..8].copy_from_slice(&version.0.to_le_bytes());
-    bytes[8..16].copy_from_slice(&version.1.to_le_bytes());
-    bytes[16..24].copy_from_slice(&version.2.to_le_bytes());
-    rapidhash(&bytes)
+/// Hash 64 bits of the id with a seed, a non-empty pre-release is hashed and combined into it.
+fn hash_name_version_seeded(name: &str, version: Option<&Version>, seed: u64) -> u64 {
+    let bytes =
+        version.map(|version| version_to_bytes(version.major, version.minor, version.patch));
+    let hash = IdAlgorithm::hash_name_version(name.as_bytes(), bytes.as_ref(), seed);
+    match version {
+        Some(version) if !version.pre.is_empty() => IdAlgorithm::combine(
+            hash,
+            IdAlgorithm::hash_seeded(version.pre.as_bytes(), seed),
+            seed,
+        ),
+        _ => hash,
+    }
+}
+
+/// Parse a `"major.minor.patch[-pre]"` string literal into a version.
+pub(crate) fn parse_version_str(lit: &syn::LitStr) -> Result<Version> {
+    parse_version_value(&lit.value()).map_err(|message| syn::Error::new(lit.span(), message))
+}
+
+/// Parse a semver version, the build metadata is ignored, as it doesn't take part in version precedence.
+pub(crate) fn parse_version_value(value: &str) -> std::result::Result<Version, String> {
+    let version = value.split_once('+').map_or(value, |(version, _)| version);
+    let (version, pre) = match version.split_once('-') {
+        Some((_, pre)) if !valid_prerelease(pre) => {
+            return Err(format!(
+                "invalid pre-release \"{}\", expected dot separated identifiers like \"beta.3\"",
+                pre
+            ));
+        }
+        Some(split) => split,
+        None => (version, ""),
+    };
+    if pre.len() > MAX_PRERELEASE_LEN {
+        return Err(format!(
+            "the pre-release \"{}\" is longer than {} bytes",
+            pre, MAX_PRERELEASE_LEN
+        ));
+    }
+    let parts = version
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<std::result::Result<Vec<_>, _>>();
+    match parts.as_deref() {
+        Ok([major, minor, patch]) => Ok(Version {
+            major: *major,
+            minor: *minor,
+            patch: *patch,
+            pre: pre.to_string(),
+        }),
+        _ => Err(format!(
+            "expected a version like \"1.2.0\" or \"2.0.0-beta.3\", found \"{}\"",
+            value
+        )),
+    }
+}
+
+/// If it's dot separated identifiers of `[0-9A-Za-z-]`, and the numeric ones have no leading zeros.
+fn valid_prerelease(pre: &str) -> bool {
+    pre.split('.').all(|identifier| {
+        let numeric = identifier.bytes().all(|b| b.is_ascii_digit());
+        !identifier.is_empty()
+            && identifier
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+            && !(numeric && identifier.len() > 1 && identifier.starts_with('0'))
+    })
+}
+
+/// Parse `crate` as the `CARGO_PKG_VERSION` of the crate being compiled.
+pub(crate) fn parse_crate_version(token: &Token![crate]) -> Result<Version> {
+    let value = std::env::var("CARGO_PKG_VERSION").map_err(|_| {
+        syn::Error::new(
+            token.span,
+            "`CARGO_PKG_VERSION` is not set, `crate` can only be used when building with cargo",
+        )
+    })?;
+    parse_version_value(&value).map_err(|message| {
+        syn::Error::new(
+            token.span,
+            format!("invalid `CARGO_PKG_VERSION` of the crate: {}", message),
+        )
+    })
+}
+
+/// Parse the argument of `#[version(...)]`, one of:
+///
+/// - a tuple `(major, minor, patch)`,
+/// - a string literal `"major.minor.patch"`,
+/// - `crate`, the version of the crate being compiled,
+/// - `rustc`, `fixed_type_id::RUSTC_VERSION`, which is only known in const context, returns `None`.
+fn parse_version_attr(attr: &Attribute) -> Result<Option<Version>> {
+    attr.parse_args_with(|input: ParseStream| {
+        if input.peek(Token![crate]) {
+            return parse_crate_version(&input.parse()?).map(Some);
+        }
+        if input.peek(syn::LitStr) {
+            return parse_version_str(&input.parse()?).map(Some);
+        }
+        if input.peek(syn::Ident) && input.fork().parse::<syn::Ident>()? == "rustc" {
+            input.parse::<syn::Ident>()?;
+            return Ok(None);
+        }
+        let expr: Expr = input.parse()?;
+        let Expr::Tuple(tuple) = &expr else {
+            return Err(syn::Error::new(
+                expr.span(),
+                "expected a version like `(1, 2, 0)`, `\"1.2.0\"`, `crate` or `rustc`",
+            ));
+        };
+        let parts = tuple
+            .elems
+            .iter()
+            .map(version_part)
+            .collect::<Result<Vec<_>>>()?;
+        match parts.as_slice() {
+            [major, minor, patch] => Ok(Some(Version::new(*major, *minor, *patch))),
+            _ => Err(syn::Error::new(
+                tuple.span(),
+                format!(
+                    "expected 3 parts `(major, minor, patch)`, found {}",
+                    parts.len()
+                ),
+            )),
+        }
+    })
+}
+
+/// Parse an integer part of a version tuple, which may be wrapped in a group when passed by a `macro_rules!`.
+fn version_part(expr: &Expr) -> Result<u64> {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Int(lit_int),
+            ..
+        }) => lit_int.base10_parse(),
+        Expr::Group(group) => version_part(&group.expr),
+        _ => Err(syn::Error::new(expr.span(), "expected an integer")),
+    }
 }
 
 #[derive(Clone, Debug)]
 enum Type {
     Path(syn::Path),
     Tuple(Vec<(RefType, Type)>),
+    /// A slice, an array, a raw pointer, a fn pointer or a trait object with several bounds,
+    /// with its canonical name built by [`canonical_name`].
+    Other {
+        ty: syn::Type,
+        name: String,
+    },
+}
+
+impl Type {
+    /// The span of the path or the type, or the call site for a tuple.
+    fn span(&self) -> proc_macro2::Span {
+        match self {
+            Type::Path(path) => path.span(),
+            Type::Tuple(_) => proc_macro2::Span::call_site(),
+            Type::Other { ty, .. } => ty.span(),
+        }
+    }
+
+    fn other(ty: syn::Type) -> Result<Self> {
+        let name = canonical_name(&ty)?;
+        Ok(Type::Other { ty, name })
+    }
 }
 
 // Add helper function to parse types recursively
+/// A generic parameter of a generic path, like `'a`, `T: FixedTypeId` or `const N: usize`,
+/// or a generic argument of a concrete path, like `u8` or `16`.
+#[derive(Clone, Debug)]
+enum PathGeneric {
+    Argument(GenericArgument),
+    Const(syn::ConstParam),
+}
+
 fn parse_type(input: ParseStream) -> Result<(RefType, Type)> {
+    if starts_other_type(input) {
+        return Ok((RefType::None, Type::other(input.parse()?)?));
+    }
     let mut ref_type = RefType::None;
 
     // Check for reference
     if input.peek(Token![&]) {
         let _ = input.parse::<Token![&]>()?;
+        // lifetimes are omitted from the names
+        let _ = input.parse::<Option<syn::Lifetime>>()?;
         let mutability = input.parse::<Token![mut]>().ok();
         ref_type = if mutability.is_some() {
             RefType::Mutable
@@ -74,11 +313,222 @@ fn parse_type(input: ParseStream) -> Result<(RefType, Type)> {
         Ok((ref_type, Type::Tuple(elements)))
     } else {
         // Parse as path
-        let path = input.parse()?;
+        let path = parse_path(input)?;
         Ok((ref_type, Type::Path(path)))
     }
 }
 
+/// Whether the input starts with a slice, an array, a raw pointer or a fn pointer, maybe behind a reference,
+/// which are parsed by `syn` as a [`Type::Other`].
+fn starts_other_type(input: ParseStream) -> bool {
+    let fork = input.fork();
+    if fork.parse::<Token![&]>().is_ok() {
+        let _ = fork.parse::<Option<syn::Lifetime>>();
+        let _ = fork.parse::<Option<Token![mut]>>();
+    }
+    fork.peek(syn::token::Bracket)
+        || fork.peek(Token![*])
+        || fork.peek(Token![fn])
+        || fork.peek(Token![unsafe])
+        || fork.peek(Token![extern])
+        || fork.peek(Token![for])
+}
+
+/// Whether the input is a trait object with more than one bound, like `dyn Trait + Send`.
+fn is_multi_bound_dyn(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.parse::<Token![dyn]>().is_ok() && parse_path(&fork).is_ok() && fork.peek(Token![+])
+}
+
+/// The canonical name of a type parsed by `syn`:
+///
+/// - paths are written without spaces, like the other names, e.g. `my::Foo<u8>`;
+/// - `[T]`, `[T;N]`, `*const T` and `*mut T`;
+/// - references without lifetimes, named like the built-in implementations and the path form: `&T`, `&mutT`,
+///   and `&[T]`, `&mut [T]` for slices;
+/// - lifetimes are omitted everywhere, like `'a` in `my::Foo<'a, u8>`;
+/// - `fn(A,B) -> R`, with `-> ()` when there is no return type, prefixed by `unsafe ` and `extern "C" `,
+///   where `extern` without an ABI is `extern "C"`;
+/// - `dyn Trait + Send + Sync`, with the auto traits after the other bounds in a fixed order,
+///   and the `'static` bound omitted, since `dyn Trait` is `dyn Trait + 'static` in an impl.
+fn canonical_name(ty: &syn::Type) -> Result<String> {
+    let name = match ty {
+        syn::Type::Path(type_path) if type_path.qself.is_none() => path_name(&type_path.path)?,
+        syn::Type::Path(type_path) => format!("{}", quote!(#type_path)).replace(" ", ""),
+        syn::Type::Paren(paren) => canonical_name(&paren.elem)?,
+        syn::Type::Group(group) => canonical_name(&group.elem)?,
+        syn::Type::Never(_) => "!".to_string(),
+        syn::Type::Slice(slice) => format!("[{}]", canonical_name(&slice.elem)?),
+        syn::Type::Array(array) => {
+            let len = &array.len;
+            let len = format!("{}", quote!(#len)).replace(" ", "");
+            format!("[{};{}]", canonical_name(&array.elem)?, len)
+        }
+        syn::Type::Ptr(ptr) => {
+            let mutability = if ptr.mutability.is_some() {
+                "mut"
+            } else {
+                "const"
+            };
+            format!("*{} {}", mutability, canonical_name(&ptr.elem)?)
+        }
+        syn::Type::Reference(reference) => {
+            let mutability = if reference.mutability.is_some() {
+                "mut"
+            } else {
+                ""
+            };
+            // the same names as the built-in `&T`, `&mut T`, `&[T]` and `&mut [T]`
+            let space = if matches!(*reference.elem, syn::Type::Slice(_)) && !mutability.is_empty()
+            {
+                " "
+            } else {
+                ""
+            };
+            format!("&{mutability}{space}{}", canonical_name(&reference.elem)?)
+        }
+        syn::Type::Tuple(tuple) => {
+            let elems = tuple
+                .elems
+                .iter()
+                .map(canonical_name)
+                .collect::<Result<Vec<_>>>()?;
+            if elems.len() == 1 {
+                format!("({},)", elems[0])
+            } else {
+                format!("({})", elems.join(","))
+            }
+        }
+        syn::Type::BareFn(bare_fn) => {
+            if let Some(lifetimes) = &bare_fn.lifetimes {
+                return Err(syn::Error::new(
+                    lifetimes.span(),
+                    "higher-ranked fn pointers are not supported",
+                ));
+            }
+            if let Some(variadic) = &bare_fn.variadic {
+                return Err(syn::Error::new_spanned(
+                    variadic,
+                    "variadic fn pointers are not supported",
+                ));
+            }
+            let mut name = String::new();
+            if bare_fn.unsafety.is_some() {
+                name.push_str("unsafe ");
+            }
+            if let Some(abi) = &bare_fn.abi {
+                let abi = abi
+                    .name
+                    .as_ref()
+                    .map_or("C".to_string(), |name| name.value());
+                name.push_str(&format!("extern {abi:?} "));
+            }
+            let inputs = bare_fn
+                .inputs
+                .iter()
+                .map(|arg| canonical_name(&arg.ty))
+                .collect::<Result<Vec<_>>>()?;
+            let output = match &bare_fn.output {
+                syn::ReturnType::Default => "()".to_string(),
+                syn::ReturnType::Type(_, ty) => canonical_name(ty)?,
+            };
+            name.push_str(&format!("fn({}) -> {}", inputs.join(","), output));
+            name
+        }
+        syn::Type::TraitObject(trait_object) => {
+            let mut bounds = Vec::new();
+            for bound in &trait_object.bounds {
+                match bound {
+                    TypeParamBound::Trait(trait_bound) => {
+                        if !matches!(trait_bound.modifier, syn::TraitBoundModifier::None) {
+                            return Err(syn::Error::new(
+                                trait_bound.span(),
+                                "`?Trait` bounds are not allowed in a trait object",
+                            ));
+                        }
+                        let path = &trait_bound.path;
+                        let order = path
+                            .segments
+                            .last()
+                            .and_then(|segment| {
+                                AUTO_TRAITS.iter().position(|auto| segment.ident == auto)
+                            })
+                            .map_or(0, |position| position + 1);
+                        bounds.push((order, path_name(path)?));
+                    }
+                    TypeParamBound::Lifetime(lifetime) if lifetime.ident == "static" => {}
+                    _ => {
+                        return Err(syn::Error::new(
+                            bound.span(),
+                            "only trait bounds and `'static` are supported in a trait object",
+                        ))
+                    }
+                }
+            }
+            // stable, so the bounds other than the auto traits keep their order
+            bounds.sort_by_key(|(order, _)| *order);
+            let bounds: Vec<_> = bounds.into_iter().map(|(_, path)| path).collect();
+            format!("dyn {}", bounds.join(" + "))
+        }
+        _ => {
+            return Err(syn::Error::new(
+                ty.span(),
+                "unsupported type, expected a path, a tuple, a reference, a slice, an array, \
+                 a raw pointer, a fn pointer or a trait object",
+            ))
+        }
+    };
+    Ok(name)
+}
+
+/// The canonical name of a path, without spaces and lifetimes, its generic arguments are named by [`canonical_name`].
+fn path_name(path: &syn::Path) -> Result<String> {
+    let mut name = String::new();
+    if path.leading_colon.is_some() {
+        name.push_str("::");
+    }
+    for (index, segment) in path.segments.iter().enumerate() {
+        if index > 0 {
+            name.push_str("::");
+        }
+        name.push_str(&segment.ident.to_string());
+        match &segment.arguments {
+            PathArguments::None => {}
+            PathArguments::AngleBracketed(arguments) => {
+                let mut args = Vec::new();
+                for arg in &arguments.args {
+                    match arg {
+                        GenericArgument::Lifetime(_) => {}
+                        GenericArgument::Type(ty) => args.push(canonical_name(ty)?),
+                        GenericArgument::AssocType(assoc) if assoc.generics.is_none() => {
+                            args.push(format!("{}={}", assoc.ident, canonical_name(&assoc.ty)?))
+                        }
+                        other => args.push(format!("{}", quote!(#other)).replace(" ", "")),
+                    }
+                }
+                if !args.is_empty() {
+                    name.push_str(&format!("<{}>", args.join(",")));
+                }
+            }
+            PathArguments::Parenthesized(arguments) => {
+                let inputs = arguments
+                    .inputs
+                    .iter()
+                    .map(canonical_name)
+                    .collect::<Result<Vec<_>>>()?;
+                name.push_str(&format!("({})", inputs.join(",")));
+                if let syn::ReturnType::Type(_, ty) = &arguments.output {
+                    name.push_str(&format!(" -> {}", canonical_name(ty)?));
+                }
+            }
+        }
+    }
+    Ok(name)
+}
+
+/// The auto traits in the order of the canonical name of a trait object.
+const AUTO_TRAITS: [&str; 5] = ["Send", "Sync", "Unpin", "UnwindSafe", "RefUnwindSafe"];
+
 // Update path_to_string to handle tuples
 fn type_to_string(ref_type: RefType, is_dyn: bool, ty: &Type) -> String {
     let ref_str = match ref_type {
@@ -89,9 +539,9 @@ fn type_to_string(ref_type: RefType, is_dyn: bool, ty: &Type) -> String {
 
     let type_str = match ty {
         Type::Path(path) => {
-            let is_dyn_str = if is_dyn { "dyn" } else { "" };
+            let is_dyn_str = if is_dyn { "dyn " } else { "" };
             let path_str = format! {"{}", quote!(#path)}.replace(" ", "");
-            format!("{} {}", is_dyn_str, path_str)
+            format!("{}{}", is_dyn_str, path_str)
         }
         Type::Tuple(elements) => {
             let elem_strs: Vec<_> = elements
@@ -100,6 +550,7 @@ fn type_to_string(ref_type: RefType, is_dyn: bool, ty: &Type) -> String {
                 .collect();
             format!("({})", elem_strs.join(","))
         }
+        Type::Other { name, .. } => name.clone(),
     };
 
     format!("{}{}", ref_str, type_str).trim().to_string()
@@ -115,12 +566,12 @@ fn type_to_string_wo_generic(ref_type: RefType, is_dyn: bool, ty: &Type) -> Stri
 
     let type_str = match ty {
         Type::Path(path) => {
-            let is_dyn_str = if is_dyn { "dyn" } else { "" };
+            let is_dyn_str = if is_dyn { "dyn " } else { "" };
             let mut path = path.clone();
             let last = path.segments.pop().unwrap().into_tuple().0.ident;
             let path_str = format! {"{}", quote!(#path)}.replace(" ", "");
             let path_str = format! {"{}{}", path_str, last};
-            format!("{} {}", is_dyn_str, path_str)
+            format!("{}{}", is_dyn_str, path_str)
         }
         Type::Tuple(elements) => {
             let elem_strs: Vec<_> = elements
@@ -129,39 +580,159 @@ fn type_to_string_wo_generic(ref_type: RefType, is_dyn: bool, ty: &Type) -> Stri
                 .collect();
             format!("({})", elem_strs.join(","))
         }
+        Type::Other { name, .. } => name.clone(),
     };
 
     format!("{}{}", ref_str, type_str).trim().to_string()
 }
 
-fn extract_generics(path: &syn::Path) -> Vec<GenericArgument> {
+/// Parse a path, the generic arguments of its last segment may contain const generic parameters,
+/// like `my::Buf<const N: usize, T: FixedTypeId>`, which are not valid in a [`syn::Path`].
+fn parse_path(input: ParseStream) -> Result<syn::Path> {
+    let fork = input.fork();
+    if let Ok(path) = fork.parse::<syn::Path>() {
+        input.advance_to(&fork);
+        return Ok(path);
+    }
+    let mut path = syn::Path::parse_mod_style(input)?;
+    let colon2_token = input.parse::<Option<Token![::]>>()?;
+    let lt_token = input.parse::<Token![<]>()?;
+    let mut args = Punctuated::new();
+    while !input.peek(Token![>]) {
+        args.push_value(input.parse::<GenericArgumentOrConst>()?.0);
+        if input.peek(Token![>]) {
+            break;
+        }
+        args.push_punct(input.parse::<Token![,]>()?);
+    }
+    let gt_token = input.parse::<Token![>]>()?;
+    let last = path.segments.last_mut().unwrap();
+    last.arguments = PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
+        colon2_token,
+        lt_token,
+        args,
+        gt_token,
+    });
+    Ok(path)
+}
+
+/// A generic argument, where a const generic parameter `const N: usize` is stored as
+/// a [`GenericArgument::Const`] of the verbatim tokens, see [`extract_generics`].
+struct GenericArgumentOrConst(GenericArgument);
+
+impl Parse for GenericArgumentOrConst {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![const]) {
+            let const_param: syn::ConstParam = input.parse()?;
+            return Ok(GenericArgumentOrConst(GenericArgument::Const(
+                Expr::Verbatim(const_param.into_token_stream()),
+            )));
+        }
+        input.parse().map(GenericArgumentOrConst)
+    }
+}
+
+fn extract_generics(path: &syn::Path) -> Vec<PathGeneric> {
     path.segments
         .last()
         .and_then(|seg| match &seg.arguments {
-            PathArguments::AngleBracketed(angle_bracketed) => {
-                Some(angle_bracketed.args.clone().into_iter().collect())
-            }
+            PathArguments::AngleBracketed(angle_bracketed) => Some(
+                angle_bracketed
+                    .args
+                    .iter()
+                    .map(|arg| match arg {
+                        GenericArgument::Const(Expr::Verbatim(tokens)) => {
+                            match syn::parse2::<syn::ConstParam>(tokens.clone()) {
+                                Ok(const_param) => PathGeneric::Const(const_param),
+                                Err(_) => PathGeneric::Argument(arg.clone()),
+                            }
+                        }
+                        _ => PathGeneric::Argument(arg.clone()),
+                    })
+                    .collect(),
+            ),
             _ => None,
         })
         .unwrap_or_default()
 }
 
+/// The name segment of a const generic parameter, rendered in const context.
+pub(crate) fn const_param_name(const_param: &syn::ConstParam) -> Result<proc_macro2::TokenStream> {
+    let renderer = match &const_param.ty {
+        syn::Type::Path(type_path) if type_path.qself.is_none() => type_path
+            .path
+            .get_ident()
+            .and_then(|ty| match ty.to_string().as_str() {
+                "usize" => Some("ConstUsize"),
+                "u8" => Some("ConstU8"),
+                "u16" => Some("ConstU16"),
+                "u32" => Some("ConstU32"),
+                "u64" => Some("ConstU64"),
+                "u128" => Some("ConstU128"),
+                "isize" => Some("ConstIsize"),
+                "i8" => Some("ConstI8"),
+                "i16" => Some("ConstI16"),
+                "i32" => Some("ConstI32"),
+                "i64" => Some("ConstI64"),
+                "i128" => Some("ConstI128"),
+                "bool" => Some("ConstBool"),
+                "char" => Some("ConstChar"),
+                _ => None,
+            }),
+        _ => None,
+    };
+    let Some(renderer) = renderer else {
+        return Err(syn::Error::new_spanned(
+            &const_param.ty,
+            "unsupported const generic parameter type, expected an integer type, `bool` or `char`",
+        ));
+    };
+    let renderer = syn::Ident::new(renderer, proc_macro2::Span::call_site());
+    let param = &const_param.ident;
+    Ok(quote! { self::__fixed_type_id_const_param::#renderer::<#param>::STR })
+}
+
 /// Custom structure to represent `dyn TraitName` and multiple attributes
 #[derive(Debug)]
 struct GeneralTypesInput {
-    store_in_file: Option<String>, // The value of `store_in_file`, e.g. "types.toml"
-    version: (u64, u64, u64),
+    store_in_file: Option<syn::LitStr>, // The value of `store_in_file`, e.g. "types.toml"
+    lock_in_file: Option<syn::LitStr>,  // The value of `lock_in_file`, e.g. "ids.lock.toml"
+    version: Version,
+    /// `#[version(rustc)]`, the `TYPE_VERSION` is `fixed_type_id::RUSTC_VERSION`, and the id is hashed with [`Self::version`].
+    rustc_version: bool,
     equal_to: Option<syn::Path>,
     omit_version_hash: bool,
     random_id: bool,
+    /// The generic parameters declared by `#[phantom(T, U = "_")]`, without a `FixedTypeId` bound,
+    /// omitted from the type name, or named by the placeholder.
+    phantom: Vec<Phantom>,
     types: Vec<Type>,
-    /// current generics is always empty, because that is parsed to [`Self::paths`]
-    generics: Vec<Vec<GenericArgument>>,
+    /// the generic arguments of the last segment of each path, empty for tuples
+    generics: Vec<Vec<PathGeneric>>,
     is_dyn: Vec<bool>,
     /// ref type at outer level
     ref_type: Vec<RefType>,
 }
 
+/// A generic parameter declared by `#[phantom(...)]`, like `S` or `T = "_"`.
+#[derive(Debug)]
+struct Phantom {
+    ident: syn::Ident,
+    placeholder: Option<syn::LitStr>,
+}
+
+impl Parse for Phantom {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = input.parse()?;
+        let placeholder = if input.parse::<Option<Token![=]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Phantom { ident, placeholder })
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 enum RefType {
     None,
@@ -172,40 +743,25 @@ enum RefType {
 impl Parse for GeneralTypesInput {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut store_in_file = None;
+        let mut lock_in_file = None;
         // Parse the outer attributes (e.g., #[store_in_file] etc.)
         let attrs: Vec<Attribute> = input.call(Attribute::parse_outer)?;
-        let mut version = (0, 0, 0);
+        let mut version = Version::default();
+        let mut rustc_version = false;
         let mut equal_to = None;
         let mut omit_version_hash = false;
         let mut random_id = false;
+        let mut phantom = Vec::new();
         for attr in attrs {
             if attr.path().is_ident("store_in_file") {
-                if let Expr::Lit(expr_lit) = attr.parse_args()? {
-                    if let Lit::Str(lit_str) = expr_lit.lit {
-                        store_in_file = Some(lit_str.value());
-                    }
-                }
+                store_in_file = Some(attr.parse_args::<syn::LitStr>()?);
+            } else if attr.path().is_ident("lock_in_file") {
+                lock_in_file = Some(attr.parse_args::<syn::LitStr>()?);
             } else if attr.path().is_ident("version") {
-                if let Expr::Tuple(expr_tuple) = attr.parse_args()? {
-                    let mut version_parts = vec![];
-                    for elem in expr_tuple.elems {
-                        if let Expr::Lit(expr_lit) = elem {
-                            if let Lit::Int(lit_int) = expr_lit.lit {
-                                version_parts.push(lit_int.base10_parse::<u64>().unwrap());
-                            }
-                        } else if let Expr::Group(expr_group) = elem {
-                            // used inside declare macro
-                            let inner = expr_group.expr;
-                            if let Expr::Lit(expr_lit) = *inner {
-                                if let Lit::Int(lit_int) = expr_lit.lit {
-                                    version_parts.push(lit_int.base10_parse::<u64>().unwrap());
-                                }
-                            }
-                        }
-                    }
-                    assert_eq!(version_parts.len(), 3);
-                    version = (version_parts[0], version_parts[1], version_parts[2]);
-                }
+                // the id of a `rustc` versioned type is hashed with `0.0.0`
+                let parsed = parse_version_attr(&attr)?;
+                rustc_version = parsed.is_none();
+                version = parsed.unwrap_or_default();
             } else if attr.path().is_ident("equal_to") {
                 // all types scope in this macro will have the same TypeIdEqualTo type specified by this attribute
                 let equal_to_target: syn::Path = attr.parse_args()?;
@@ -214,6 +770,16 @@ impl Parse for GeneralTypesInput {
                 omit_version_hash = true;
             } else if attr.path().is_ident("random_id") {
                 random_id = true;
+            } else if attr.path().is_ident("phantom") {
+                phantom.extend(
+                    attr.parse_args_with(Punctuated::<Phantom, Token![,]>::parse_terminated)?,
+                );
+            } else if !attr.path().is_ident("doc") {
+                return Err(syn::Error::new(
+                    attr.path().span(),
+                    "unknown attribute, expected one of `version`, `store_in_file`, `lock_in_file`, \
+                     `equal_to`, `omit_version_hash`, `random_id`, `phantom`",
+                ));
             }
         }
 
@@ -223,12 +789,18 @@ impl Parse for GeneralTypesInput {
         let mut ref_type = Vec::new();
         // Parse multiple definitions
         while !input.is_empty() {
-            // Check if it's a dyn trait
-            let is_dyn_current = input.parse::<Token![dyn]>().is_ok();
+            // A trait object with several bounds is parsed as a whole, and named with its bounds
+            let (is_dyn_current, (current_ref_type, type_value)) = if is_multi_bound_dyn(input) {
+                let trait_object: syn::TypeTraitObject = input.parse()?;
+                let ty = Type::other(syn::Type::TraitObject(trait_object))?;
+                (false, (RefType::None, ty))
+            } else {
+                // Check if it's a dyn trait
+                let is_dyn_current = input.parse::<Token![dyn]>().is_ok();
+                // Parse type which could be reference, tuple, path or other types
+                (is_dyn_current, parse_type(input)?)
+            };
             is_dyn.push(is_dyn_current);
-
-            // Parse type which could be reference, tuple or path
-            let (current_ref_type, type_value) = parse_type(input)?;
             ref_type.push(current_ref_type);
 
             // Optionally parse "::" before generic arguments, when parsed by `syn``
@@ -251,12 +823,60 @@ impl Parse for GeneralTypesInput {
             }
         }
 
+        // a phantom parameter must be an unbounded parameter of one of the types
+        for declared in &phantom {
+            let mut params = generics
+                .iter()
+                .flatten()
+                .filter_map(|generic| match generic {
+                    PathGeneric::Argument(GenericArgument::Constraint(constraint))
+                        if constraint.ident == declared.ident =>
+                    {
+                        Some(constraint)
+                    }
+                    _ => None,
+                });
+            let Some(param) = params.next() else {
+                return Err(syn::Error::new(
+                    declared.ident.span(),
+                    format!("no generic parameter `{}` in the types", declared.ident),
+                ));
+            };
+            if has_fixed_type_id_bound(param) {
+                return Err(syn::Error::new(
+                    declared.ident.span(),
+                    format!(
+                        "`{}` is bounded by `FixedTypeId` and named by its type, it can't be phantom",
+                        declared.ident
+                    ),
+                ));
+            }
+        }
+
+        if let Some(lock_in_file) = &lock_in_file {
+            if store_in_file.is_some() {
+                return Err(syn::Error::new(
+                    lock_in_file.span(),
+                    "`lock_in_file` already stores the ids, remove `store_in_file`",
+                ));
+            }
+            if random_id {
+                return Err(syn::Error::new(
+                    lock_in_file.span(),
+                    "`random_id` generates a different id for each build, it can't be locked",
+                ));
+            }
+        }
+
         Ok(GeneralTypesInput {
             store_in_file,
+            lock_in_file,
             version,
+            rustc_version,
             equal_to,
             omit_version_hash,
             random_id,
+            phantom,
             types,
             generics,
             is_dyn,
@@ -265,45 +885,59 @@ impl Parse for GeneralTypesInput {
     }
 }
 
-fn store_id_in_file(file_name: &str, type_names: &[String], idvec: &[u64]) -> std::io::Result<()> {
-    use std::io::Write;
-    let path = std::path::Path::new(file_name);
-    let mut ids = std::collections::HashMap::new();
-
-    // Read existing IDs from the file
-    if path.exists() {
-        let file = File::open(path)?;
-        let reader = std::io::BufReader::new(file);
-        for line in std::io::BufRead::lines(reader) {
-            let line = line?;
-            let parts: Vec<&str> = line.split('=').collect();
-            if parts.len() == 2 {
-                ids.insert(
-                    parts[0].trim().to_string(),
-                    parts[1].trim().parse::<u64>().unwrap(),
-                );
-            }
-        }
-        for (type_name, id) in type_names.iter().zip(idvec.iter()) {
-            // Update or add the new ID
-            let entry = ids.entry(type_name.to_string()).or_insert(*id);
-            *entry = *id;
+/// Whether a generic parameter like `T: FixedTypeId + Clone` is bounded by `FixedTypeId`.
+fn has_fixed_type_id_bound(constraint: &syn::Constraint) -> bool {
+    constraint.bounds.iter().any(|bound| match bound {
+        TypeParamBound::Trait(TraitBound { path, .. }) => {
+            path.segments.last().unwrap().ident == "FixedTypeId"
         }
+        _ => false,
+    })
+}
 
-        // Write all IDs back to the file
-        let mut file = std::fs::OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(path)?;
-
-        for (name, id) in ids {
-            writeln!(file, "{} = {}", name, id)?;
+/// Record the ids into the lockfile, and if `lock` is `true`, check them against the locked ids first.
+fn store_ids_in_file(
+    file_name: &syn::LitStr,
+    entries: Vec<(LockEntry, proc_macro2::Span)>,
+    lock: bool,
+) -> Result<()> {
+    let mut lockfile =
+        Lockfile::load(&file_name.value()).map_err(|e| syn::Error::new(file_name.span(), e))?;
+    let invocation = proc_macro::Span::call_site();
+    lockfile.prune(
+        &invocation.file(),
+        invocation.start().line() as u32..=invocation.end().line() as u32,
+        &entries
+            .iter()
+            .map(|(entry, _)| entry.clone())
+            .collect::<Vec<_>>(),
+    );
+    let mut errors: Option<syn::Error> = None;
+    for (entry, span) in entries {
+        if lock {
+            if let Err(e) = lockfile.check(&entry) {
+                let error = syn::Error::new(span, e);
+                match &mut errors {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+                continue;
+            }
         }
-        Ok(())
-    } else {
-        panic!("File {} doesn't exist!", file_name);
+        lockfile.insert(entry);
     }
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+    lockfile
+        .save()
+        .map_err(|e| syn::Error::new(file_name.span(), e))
+}
+
+/// The location of a span, like `src/lib.rs:12`.
+fn span_location(span: proc_macro2::Span) -> String {
+    let span = span.unwrap();
+    format!("{}:{}", span.file(), span.line())
 }
 
 fn path_to_prefix_path(path: &syn::Path) -> (syn::Path, syn::Path) {
@@ -324,6 +958,39 @@ fn path_to_prefix_path(path: &syn::Path) -> (syn::Path, syn::Path) {
     (prefix, name)
 }
 
+/// Like a path, the paths and traits in a [`Type::Other`] are named by their last segment,
+/// the prefix is only a part of the type name.
+fn strip_prefixes(ty: &mut syn::Type) {
+    match ty {
+        syn::Type::Path(type_path) if type_path.qself.is_none() => {
+            type_path.path = path_to_prefix_path(&type_path.path).1;
+        }
+        syn::Type::Paren(paren) => strip_prefixes(&mut paren.elem),
+        syn::Type::Group(group) => strip_prefixes(&mut group.elem),
+        syn::Type::Slice(slice) => strip_prefixes(&mut slice.elem),
+        syn::Type::Array(array) => strip_prefixes(&mut array.elem),
+        syn::Type::Ptr(ptr) => strip_prefixes(&mut ptr.elem),
+        syn::Type::Reference(reference) => strip_prefixes(&mut reference.elem),
+        syn::Type::Tuple(tuple) => tuple.elems.iter_mut().for_each(strip_prefixes),
+        syn::Type::BareFn(bare_fn) => {
+            for arg in bare_fn.inputs.iter_mut() {
+                strip_prefixes(&mut arg.ty);
+            }
+            if let syn::ReturnType::Type(_, output) = &mut bare_fn.output {
+                strip_prefixes(output);
+            }
+        }
+        syn::Type::TraitObject(trait_object) => {
+            for bound in trait_object.bounds.iter_mut() {
+                if let TypeParamBound::Trait(trait_bound) = bound {
+                    trait_bound.path = path_to_prefix_path(&trait_bound.path).1;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 fn type_to_token_stream(ref_type: RefType, is_dyn: bool, ty: &Type) -> proc_macro2::TokenStream {
     let ref_type = match ref_type {
         RefType::None => quote! {},
@@ -347,23 +1014,51 @@ fn type_to_token_stream(ref_type: RefType, is_dyn: bool, ty: &Type) -> proc_macr
                 .map(|(ref_type, ty)| type_to_token_stream(*ref_type, false, ty));
             quote! { (#(#elem_tokens),*) }
         }
+        Type::Other { ty, .. } => {
+            let mut ty = ty.clone();
+            strip_prefixes(&mut ty);
+            ty.into_token_stream()
+        }
     };
 
     quote! { #ref_type #type_tokens }
 }
 
+/// Register a concrete type into the registry of `fixed_type_id`, when feature `registry` is enabled.
+///
+/// The items are reached by the absolute path `::fixed_type_id::registry`, so they don't depend on the imports of the caller,
+/// the feature can be enabled by any crate in the dependency graph.
+pub(crate) fn register_type(
+    ty: &proc_macro2::TokenStream,
+    equal_to: Option<&dyn ToTokens>,
+) -> proc_macro2::TokenStream {
+    if cfg!(feature = "registry") {
+        let equal_to = equal_to.map(|target| quote! { .equal_to::<#target>() });
+        quote! {
+            const _: () = {
+                #[::fixed_type_id::registry::__private::distributed_slice(::fixed_type_id::registry::TYPE_REGISTRY)]
+                #[linkme(crate = ::fixed_type_id::registry::__private::linkme)]
+                static ENTRY: ::fixed_type_id::registry::__private::TypeEntry =
+                    ::fixed_type_id::registry::__private::TypeEntry::of::<#ty>() #equal_to;
+            };
+        }
+    } else {
+        quote! {}
+    }
+}
+
 fn impl_fixed_type_id_for_type(
-    export_names: &mut Vec<String>,
-    export_hashes: &mut Vec<u64>,
+    export_entries: &mut Vec<(LockEntry, proc_macro2::Span)>,
     export_implementations: &mut Vec<proc_macro2::TokenStream>,
     ast: &GeneralTypesInput,
     target_type: &Type,
     index: usize,
-) {
-    let id_type = syn::parse_str::<syn::Type>("u64").unwrap();
-    let major = ast.version.0;
-    let minor = ast.version.1;
-    let patch = ast.version.2;
+) -> Result<()> {
+    let type_version = if ast.rustc_version {
+        quote! { self::__fixed_type_id_rustc_version }
+    } else {
+        ast.version.to_token_stream()
+    };
     // 1. determine that, current target type is concrete type or not?
     // it's a concrete type if it's
     // - SomeType
@@ -375,50 +1070,74 @@ fn impl_fixed_type_id_for_type(
     // - SomeType<T,U>
     // - SomeType<T: Add<Item = u8>,U: Add>...
     // so
-    let target_type_concrete = ast.generics[index].is_empty()
-        || ast.generics[index].iter().any(|generic_arg| {
-            matches!(
-                generic_arg,
-                GenericArgument::Type(..) | GenericArgument::Const(..)
-            )
-        });
+    // - SomeType<const N: usize, T: FixedTypeId>
+    // - dyn SomeTrait<Item = u8>, if there is no generic parameter
+    let generics = &ast.generics[index];
+    let concrete_arg = generics.iter().find_map(|generic| match generic {
+        PathGeneric::Argument(arg @ (GenericArgument::Type(..) | GenericArgument::Const(..))) => {
+            Some(arg)
+        }
+        _ => None,
+    });
+    let has_params = generics.iter().any(|generic| {
+        matches!(
+            generic,
+            PathGeneric::Const(..) | PathGeneric::Argument(GenericArgument::Constraint(..))
+        )
+    });
+    if let Some(concrete_arg) = concrete_arg {
+        if has_params {
+            return Err(syn::Error::new(
+                concrete_arg.span(),
+                "concrete arguments can't be mixed with generic parameters like `T: FixedTypeId`, \
+                 write a bound for each generic parameter",
+            ));
+        }
+    }
+    let has_assoc = generics.iter().any(|generic| {
+        matches!(
+            generic,
+            PathGeneric::Argument(GenericArgument::AssocType(..) | GenericArgument::AssocConst(..))
+        )
+    });
+    let target_type_concrete =
+        generics.is_empty() || concrete_arg.is_some() || (has_assoc && !has_params);
     if target_type_concrete {
         // manual compute name, hash id and version for concrete type
         let mut path_str = type_to_string(ast.ref_type[index], ast.is_dyn[index], target_type);
         let type_token_stream =
             type_to_token_stream(ast.ref_type[index], ast.is_dyn[index], target_type);
-        // Hash the name and version to a u64
-        export_names.push(path_str.clone());
-        // path string hash
-        let path_hash = rapidhash(path_str.as_bytes());
-        // version hash
-        let version_hash = version_to_hash(&ast.version);
+        // Hash the name and version to a RawFixedId
         let mut hash = if ast.omit_version_hash {
-            path_hash
+            hash_name_version(&path_str, None)
         } else {
-            rapid_mix(path_hash, version_hash)
+            hash_name_version(&path_str, Some(&ast.version))
         };
+        if ast.random_id {
+            hash = random();
+        }
+        // the id of `equal_to` is only known in const context, so it's not recorded
+        if ast.equal_to.is_none() && (ast.store_in_file.is_some() || ast.lock_in_file.is_some()) {
+            let span = target_type.span();
+            let entry = LockEntry {
+                name: path_str.clone(),
+                version: ast.version.clone(),
+                id: hash,
+                location: span_location(span),
+            };
+            export_entries.push((entry, span));
+        }
         if cfg!(feature = "erase_name") {
             let path_str_hash = rapidhash(path_str.as_bytes());
             path_str = format!("0x{:x}", path_str_hash);
         }
-        if ast.equal_to.is_some() {
-            // store 0u64
-            hash = 0;
-        }
-        if ast.random_id {
-            hash = random();
-        }
-        export_hashes.push(hash);
 
         let implementation = if let Some(type_id_equal_to) = &ast.equal_to {
-            // create a ident
-            let type_id_equal_to_ident = type_id_equal_to.get_ident().unwrap();
             quote! {
               impl self::FixedTypeId for #type_token_stream {
                   const TYPE_NAME: &'static str = #path_str;
-                  const TYPE_ID: self::FixedId = <#type_id_equal_to_ident as self::FixedTypeId>::TYPE_ID;
-                  const TYPE_VERSION: self::FixedVersion = self::FixedVersion::new(#major, #minor, #patch);
+                  const TYPE_ID: self::FixedId = <#type_id_equal_to as self::FixedTypeId>::TYPE_ID;
+                  const TYPE_VERSION: self::FixedVersion = #type_version;
 
                   #[inline]
                   fn ty_name(&self) -> &'static str {
@@ -440,8 +1159,8 @@ fn impl_fixed_type_id_for_type(
             quote! {
                 impl self::FixedTypeId for #type_token_stream {
                     const TYPE_NAME: &'static str = #path_str;
-                    const TYPE_ID: self::FixedId = self::FixedId(#hash as #id_type);
-                    const TYPE_VERSION: self::FixedVersion = self::FixedVersion::new(#major, #minor, #patch);
+                    const TYPE_ID: self::FixedId = self::FixedId(#hash);
+                    const TYPE_VERSION: self::FixedVersion = #type_version;
 
                     #[inline]
                     fn ty_name(&self) -> &'static str {
@@ -462,76 +1181,95 @@ fn impl_fixed_type_id_for_type(
         };
 
         export_implementations.push(implementation);
+        export_implementations.push(register_type(
+            &type_token_stream,
+            ast.equal_to.as_ref().map(|target| target as &dyn ToTokens),
+        ));
     } else {
         // for non-concrete type, we use ConstTypeName to implement it.
-        let generic_args = &ast.generics[index];
-        let generic_args_impl: Vec<_> = generic_args
-            .iter()
-            .filter_map(|generic_arg| match generic_arg {
-                GenericArgument::Lifetime(_) => Some(generic_arg.clone()),
-                GenericArgument::AssocType(_) => None,
-                GenericArgument::AssocConst(_) => None,
-                GenericArgument::Constraint(_) => Some(generic_arg.clone()),
-                _ => todo!(),
-            })
-            .collect();
-        let generic_args_target: Vec<Box<dyn ToTokens>> = generic_args
-            .iter()
-            .map(|generic_arg| match generic_arg {
-                GenericArgument::Lifetime(_) => Box::new(generic_arg.clone()),
-                GenericArgument::AssocType(_) => Box::new(generic_arg.clone()),
-                GenericArgument::AssocConst(_) => Box::new(generic_arg.clone()),
-                GenericArgument::Constraint(constraint) => {
-                    let x: Box<dyn ToTokens> = Box::new(constraint.ident.clone());
-                    x
+        let mut generic_args_impl = Vec::new();
+        let mut generic_args_target = Vec::new();
+        let mut name_args = Vec::new();
+        for generic in &ast.generics[index] {
+            match generic {
+                PathGeneric::Argument(GenericArgument::Lifetime(lifetime)) => {
+                    generic_args_impl.push(lifetime.to_token_stream());
+                    generic_args_target.push(lifetime.to_token_stream());
                 }
-                _ => todo!(),
-            })
-            .collect();
-        let generic_make_name_args_idents: Vec<_> = generic_args
-            .iter()
-            .filter_map(|generic_arg| match generic_arg {
-                GenericArgument::Constraint(constraint) => {
-                    if constraint.bounds.iter().any(|bound| match bound {
-                        TypeParamBound::Trait(TraitBound { path, .. }) => {
-                            path.segments.last().unwrap().ident == "FixedTypeId"
+                PathGeneric::Argument(
+                    arg @ (GenericArgument::AssocType(_) | GenericArgument::AssocConst(_)),
+                ) => {
+                    generic_args_target.push(arg.to_token_stream());
+                }
+                PathGeneric::Argument(GenericArgument::Constraint(constraint)) => {
+                    let ident = &constraint.ident;
+                    generic_args_impl.push(constraint.to_token_stream());
+                    generic_args_target.push(ident.to_token_stream());
+                    if has_fixed_type_id_bound(constraint) {
+                        name_args.push(quote! { #ident::TYPE_NAME });
+                    } else if let Some(phantom) = ast.phantom.iter().find(|p| p.ident == *ident) {
+                        if let Some(placeholder) = &phantom.placeholder {
+                            name_args.push(placeholder.to_token_stream());
                         }
-                        _ => false,
-                    }) {
-                        Some(constraint.ident.clone())
                     } else {
-                        None
+                        // different instantiations would share the name and the id
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            format!(
+                                "`{ident}` has no `FixedTypeId` bound, so it's not part of the type name, \
+                                 bound it by `FixedTypeId`, or declare it by `#[phantom({ident})]` to omit it \
+                                 or `#[phantom({ident} = \"_\")]` to name it by a placeholder"
+                            ),
+                        ));
                     }
                 }
-                _ => None,
-            })
-            .collect();
+                PathGeneric::Const(const_param) => {
+                    let ident = &const_param.ident;
+                    let ty = &const_param.ty;
+                    generic_args_impl.push(quote! { const #ident: #ty });
+                    generic_args_target.push(ident.to_token_stream());
+                    name_args.push(const_param_name(const_param)?);
+                }
+                PathGeneric::Argument(arg) => {
+                    return Err(syn::Error::new(
+                        arg.span(),
+                        "expected a lifetime, a parameter like `T: FixedTypeId` or `const N: usize`, \
+                         concrete arguments can't be mixed with generic parameters",
+                    ));
+                }
+            }
+        }
 
         let target_type_wo_bound = match target_type {
             Type::Path(path) => path.segments.last().unwrap().ident.clone(),
-            Type::Tuple(_) => panic!(),
+            Type::Tuple(_) => {
+                return Err(syn::Error::new(
+                    target_type.span(),
+                    "a tuple can't have generic parameters",
+                ))
+            }
+            Type::Other { .. } => {
+                return Err(syn::Error::new(
+                    target_type.span(),
+                    "only a path can have generic parameters",
+                ))
+            }
         };
         let target_type_wo_bound_str =
             type_to_string_wo_generic(ast.ref_type[index], ast.is_dyn[index], target_type);
         let mut punctuated: Vec<proc_macro2::TokenStream> = Vec::new();
-        let len = generic_make_name_args_idents.len();
-        for (index, ident) in generic_make_name_args_idents.iter().enumerate() {
-            if index == len - 1 {
-                punctuated.push(quote! {
-                    #ident::TYPE_NAME
-                });
-            } else {
-                punctuated.push(quote! {
-                    #ident::TYPE_NAME,","
-                });
+        for (index, name_arg) in name_args.into_iter().enumerate() {
+            if index != 0 {
+                punctuated.push(quote! { "," });
             }
+            punctuated.push(name_arg);
         }
 
         let implementation = if let Some(type_id_equal_to) = &ast.equal_to {
             quote! {
                 impl<#(#generic_args_impl,)*> ConstTypeName for #target_type_wo_bound<#(#generic_args_target,)*>
                 {
-                    const RAW_SLICE: &[&str] = &[
+                    const RAW_SLICE: &'static [&'static str] = &[
                         #target_type_wo_bound_str,
                         "<",
                         #(#punctuated,)*
@@ -543,9 +1281,9 @@ fn impl_fixed_type_id_for_type(
                 where
                     Self: ConstTypeName,
                 {
-                    const TYPE_NAME: &'static str = self::fstr_to_str(&Self::TYPE_NAME_FSTR);
+                    const TYPE_NAME: &'static str = <Self as self::ConstTypeName>::TYPE_NAME_STR;
                     const TYPE_ID: self::FixedId = <#type_id_equal_to as self::FixedTypeId>::TYPE_ID;
-                    const TYPE_VERSION: self::FixedVersion = self::FixedVersion::new(#major, #minor, #patch);
+                    const TYPE_VERSION: self::FixedVersion = #type_version;
                 }
             }
         } else {
@@ -554,14 +1292,16 @@ fn impl_fixed_type_id_for_type(
                     const TYPE_ID: self::FixedId = self::FixedId::from_type_name(Self::TYPE_NAME, None);
                 }
             } else {
+                // hashed with the version of the attribute, `0.0.0` for `#[version(rustc)]`
+                let version = &ast.version;
                 quote! {
-                    const TYPE_ID: self::FixedId = self::FixedId::from_type_name(Self::TYPE_NAME, Some(Self::TYPE_VERSION));
+                    const TYPE_ID: self::FixedId = self::FixedId::from_type_name(Self::TYPE_NAME, Some(#version));
                 }
             };
             quote! {
                 impl<#(#generic_args_impl,)*> ConstTypeName for #target_type_wo_bound<#(#generic_args_target,)*>
                 {
-                    const RAW_SLICE: &[&str] = &[
+                    const RAW_SLICE: &'static [&'static str] = &[
                         #target_type_wo_bound_str,
                         "<",
                         #(#punctuated,)*
@@ -573,43 +1313,105 @@ fn impl_fixed_type_id_for_type(
                 where
                     Self: ConstTypeName,
                 {
-                    const TYPE_NAME: &'static str = self::fstr_to_str(&Self::TYPE_NAME_FSTR);
+                    const TYPE_NAME: &'static str = <Self as self::ConstTypeName>::TYPE_NAME_STR;
                     #omit_version_hash_stream
-                    const TYPE_VERSION: self::FixedVersion = self::FixedVersion::new(#major, #minor, #patch);
+                    const TYPE_VERSION: self::FixedVersion = #type_version;
                 }
             }
         };
         export_implementations.push(implementation);
     }
+    Ok(())
 }
 
 pub fn fixed_type_id_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = parse_macro_input!(input as GeneralTypesInput);
-    let types_file_name = ast.store_in_file.clone();
 
     let mut implementations = Vec::new();
-
-    let mut names = vec![];
-    let mut hashes = vec![];
+    let mut entries = vec![];
 
     for (index, target_type) in ast.types.iter().enumerate() {
-        impl_fixed_type_id_for_type(
-            &mut names,
-            &mut hashes,
+        if let Err(e) = impl_fixed_type_id_for_type(
+            &mut entries,
             &mut implementations,
             &ast,
             target_type,
             index,
-        )
-    }
-    if let Some(file_name) = types_file_name {
-        if let Err(e) = store_id_in_file(&file_name, &names, &hashes) {
-            panic!("Failed to store ID in file: {}", e);
+        ) {
+            return e.to_compile_error().into();
         }
     }
-    // let id = gen_id(&types_file_name, &ast.trait_name.to_string(), gen_start);
+    let stored = if let Some(file_name) = &ast.lock_in_file {
+        store_ids_in_file(file_name, entries, true)
+    } else if let Some(file_name) = &ast.store_in_file {
+        store_ids_in_file(file_name, entries, false)
+    } else {
+        Ok(())
+    };
+    if let Err(e) = stored {
+        return e.to_compile_error().into();
+    }
 
     TokenStream::from(quote! {
         #(#implementations)*
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::fixed_type_id::canonical_name;
+    use syn::parse_quote;
+
+    #[test]
+    fn canonical_names() {
+        let names = [
+            (parse_quote!([my::A]), "[my::A]"),
+            (parse_quote!([u8; 4]), "[u8;4]"),
+            (parse_quote!(*const u8), "*const u8"),
+            (parse_quote!(*mut [u8]), "*mut [u8]"),
+            (parse_quote!(&'static mut [u8]), "&mut [u8]"),
+            (parse_quote!(&'a [u8]), "&[u8]"),
+            (parse_quote!(&'a mut u8), "&mutu8"),
+            (parse_quote!(&my::A), "&my::A"),
+            (parse_quote!(&mut dyn my::Handler), "&mutdyn my::Handler"),
+            (parse_quote!([my::A<'a, &'b mut u8>]), "[my::A<&mutu8>]"),
+            (parse_quote!(*const Vec<&'static str>), "*const Vec<&str>"),
+            (parse_quote!(*mut my::A<u8, 4>), "*mut my::A<u8,4>"),
+            (
+                parse_quote!(fn(&'a mut [u8]) -> my::A<'a>),
+                "fn(&mut [u8]) -> my::A",
+            ),
+            (
+                parse_quote!(dyn for<'a> Fn(&'a u8) + Send),
+                "dyn Fn(&u8) + Send",
+            ),
+            (
+                parse_quote!(*const dyn FnMut(u8, u16) -> u32),
+                "*const dyn FnMut(u8,u16) -> u32",
+            ),
+            (parse_quote!(fn(u8, u16) -> u32), "fn(u8,u16) -> u32"),
+            (parse_quote!(fn(x: u8)), "fn(u8) -> ()"),
+            (
+                parse_quote!(unsafe extern "C" fn(*const u8, usize) -> i32),
+                "unsafe extern \"C\" fn(*const u8,usize) -> i32",
+            ),
+            (parse_quote!(extern "C" fn()), "extern \"C\" fn() -> ()"),
+            (
+                parse_quote!(dyn Sync + my::Handler + Send + 'static),
+                "dyn my::Handler + Send + Sync",
+            ),
+            (
+                parse_quote!(*const (dyn Iterator<Item = u8> + Send)),
+                "*const dyn Iterator<Item=u8> + Send",
+            ),
+            (parse_quote!((u8,)), "(u8,)"),
+        ];
+        for (ty, name) in names {
+            assert_eq!(canonical_name(&ty).unwrap(), name);
+        }
+        assert!(canonical_name(&parse_quote!(for<'a> fn(&'a u8))).is_err());
+        assert!(canonical_name(&parse_quote!(unsafe extern "C" fn(u8, ...))).is_err());
+        assert!(canonical_name(&parse_quote!(dyn my::Handler + 'a)).is_err());
+        assert!(canonical_name(&parse_quote!(impl my::Handler)).is_err());
+    }
+}
@@ -1,4 +1,6 @@
+mod derive_fixed_type_id;
 mod fixed_type_id;
+mod lockfile;
 
 /// Macro to generate a unique id for trait object type or a general type.
 ///
@@ -40,18 +42,64 @@ mod fixed_type_id;
 /// }
 /// ```
 ///
-/// With file-based uniqueness and filename:
+/// The version can also be a semver string like `#[version("0.1.0")]`, or `#[version(crate)]`,
+/// which uses the version of the crate being compiled, so the ids are bumped with each release.
+/// `#[version(rustc)]` uses `fixed_type_id::RUSTC_VERSION`, while the id is hashed with `0.0.0`:
+/// ```
+/// mod my_crate {
+///     use fixed_type_id::fixed_type_id;
+///     use fixed_type_id::{FixedId, FixedTypeId, FixedVersion};
+///     pub trait MyTrait {}
+///     pub trait MyOtherTrait {}
+///     fixed_type_id! {
+///         #[version("0.1.0")]
+///         dyn my_crate::MyTrait
+///     }
+///     fixed_type_id! {
+///         #[version(crate)]
+///         dyn my_crate::MyOtherTrait
+///     }
+/// }
+/// ```
+///
+/// Generic parameters are named by their `FixedTypeId` bound, a parameter without it must be declared by
+/// `#[phantom(T)]` to omit it from the type name, or by `#[phantom(T = "_")]` to name it by a placeholder:
 /// ```
 /// # mod some {
+/// use fixed_type_id::prelude::*;
+///
+/// pub struct Tagged<T, Tag> {
+///     pub value: T,
+///     pub tag: core::marker::PhantomData<Tag>,
+/// }
+///
+/// fixed_type_id! {
+///     #[phantom(Tag = "_")]
+///     some::Tagged<T: FixedTypeId, Tag:>
+/// }
+/// # }
+/// ```
+///
+/// Record the ids into a lockfile, the path is relative to the crate root, and the file is created if missing.
+/// `store_in_file` only records the ids, while `lock_in_file` also fails to compile when the id of a locked type
+/// changed without a version bump, or collides with the id of another locked type:
+/// ```ignore
+/// # mod some {
 /// use fixed_type_id::fixed_type_id;
 /// use fixed_type_id::{FixedId, FixedTypeId, FixedVersion};
 ///
 /// pub trait MyTrait {}
+/// pub trait MyOtherTrait {}
 ///
 /// fixed_type_id! {
 ///     #[store_in_file("ids.toml")]
 ///     dyn MyTrait
 /// }
+///
+/// fixed_type_id! {
+///     #[lock_in_file("ids.lock.toml")]
+///     dyn MyOtherTrait
+/// }
 /// # }
 /// ```
 ///
@@ -69,3 +117,51 @@ mod fixed_type_id;
 pub fn fixed_type_id(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     fixed_type_id::fixed_type_id_impl(input)
 }
+
+/// Derive [`FixedTypeId`] for a struct, enum or union.
+///
+/// The type name defaults to the full path of the type, built from [`core::module_path`], so it
+/// never drifts from where the type actually lives. For types with generic parameters, every type
+/// parameter gets a `FixedTypeId` bound and a `ConstTypeName` implementation is generated,
+/// exactly like the generic form of [`fixed_type_id!`].
+///
+/// Options are given by `#[fixed_type_id(...)]`:
+///
+/// - `version = "x.y.z"`: set the version, default to `"0.0.0"`, `version = crate` uses the version of the crate.
+/// - `name = "..."`: use the given type name instead of the module path.
+/// - `omit_version_hash`: generate the id without hashing the version into it.
+/// - `equal_to = OtherType`: make the id equal to the id of `OtherType`.
+/// - `layout`: generate `TYPE_LAYOUT_HASH` from the fields, each field type must implement [`FixedTypeId`].
+///
+/// Like [`fixed_type_id!`], the generated code refers to `self::FixedTypeId`, `self::FixedId` etc,
+/// so import them, or the prelude, where you derive it.
+///
+/// # Examples
+///
+/// ```
+/// mod my_crate {
+///     use fixed_type_id::prelude::*;
+///
+///     #[derive(FixedTypeId)]
+///     #[fixed_type_id(name = "my_crate::Foo", version = "1.2.0")]
+///     pub struct Foo {
+///         pub x: u32,
+///     }
+///
+///     #[derive(FixedTypeId)]
+///     #[fixed_type_id(omit_version_hash)]
+///     pub struct Wrapper<T> {
+///         pub t: T,
+///     }
+/// }
+/// use fixed_type_id::prelude::*;
+/// use my_crate::*;
+///
+/// assert_eq!(<Foo as FixedTypeId>::TYPE_NAME, "my_crate::Foo");
+/// assert_eq!(<Foo as FixedTypeId>::TYPE_VERSION, FixedVersion::new(1, 2, 0));
+/// assert!(<Wrapper<u8> as FixedTypeId>::TYPE_NAME.ends_with("my_crate::Wrapper<u8>"));
+/// ```
+#[proc_macro_derive(FixedTypeId, attributes(fixed_type_id))]
+pub fn derive_fixed_type_id(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_fixed_type_id::derive_fixed_type_id_impl(input)
+}
@@ -0,0 +1,336 @@
+//! The id lockfile written by `#[store_in_file(...)]` and checked by `#[lock_in_file(...)]`.
+//!
+//! The file uses the manifest format of `fixed_type_id::manifest`: one `[[type]]` table per type,
+//! sorted by name, with `name`, `version`, `id` and `location` keys.
+//!
+//! A macro invocation only sees its own types, so the entries declared within the lines of an invocation,
+//! which it doesn't declare anymore, are pruned as renamed or removed, see [`Lockfile::prune`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::fixed_type_id::{parse_version_value, RawFixedId, Version};
+
+/// A type recorded in the lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LockEntry {
+    pub name: String,
+    pub version: Version,
+    pub id: RawFixedId,
+    /// Where the type is declared, like `src/lib.rs:12`.
+    pub location: String,
+}
+
+impl fmt::Display for LockEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} ({})",
+            self.name,
+            self.version,
+            format_id(self.id),
+            self.location
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct LockfileToml {
+    #[serde(default, rename = "type")]
+    types: Vec<LockEntryToml>,
+}
+
+#[derive(Deserialize)]
+struct LockEntryToml {
+    name: String,
+    version: String,
+    id: String,
+    #[serde(default)]
+    location: String,
+}
+
+/// The entries of a lockfile, keyed and sorted by name.
+pub(crate) struct Lockfile {
+    path: PathBuf,
+    /// The content read from the file, used to skip writing when nothing changed.
+    original: Option<String>,
+    entries: BTreeMap<String, LockEntry>,
+    /// The entries removed by [`Lockfile::prune`], to detect renamed types.
+    pruned: Vec<LockEntry>,
+}
+
+impl Lockfile {
+    /// Load the lockfile, the path is relative to the directory of the crate being compiled.
+    ///
+    /// A missing file is treated as an empty lockfile, and created by [`Lockfile::save`].
+    pub fn load(file_name: &str) -> Result<Self, String> {
+        let path = match std::env::var_os("CARGO_MANIFEST_DIR") {
+            Some(dir) => PathBuf::from(dir).join(file_name),
+            None => PathBuf::from(file_name),
+        };
+        let original = match std::fs::read_to_string(&path) {
+            Ok(content) => Some(content),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(error) => return Err(format!("failed to read `{}`: {error}", path.display())),
+        };
+        let mut entries = BTreeMap::new();
+        if let Some(content) = &original {
+            let lockfile: LockfileToml = toml::from_str(content)
+                .map_err(|error| format!("failed to parse `{}`: {error}", path.display()))?;
+            for entry in lockfile.types {
+                let entry = LockEntry {
+                    version: parse_version_value(&entry.version).map_err(|_| {
+                        format!(
+                            "invalid version `{}` of `{}` in `{}`",
+                            entry.version,
+                            entry.name,
+                            path.display()
+                        )
+                    })?,
+                    id: parse_id(&entry.id).ok_or_else(|| {
+                        format!(
+                            "invalid id `{}` of `{}` in `{}`",
+                            entry.id,
+                            entry.name,
+                            path.display()
+                        )
+                    })?,
+                    name: entry.name,
+                    location: entry.location,
+                };
+                entries.insert(entry.name.clone(), entry);
+            }
+        }
+        Ok(Lockfile {
+            path,
+            original,
+            entries,
+            pruned: Vec::new(),
+        })
+    }
+
+    /// Remove the entries declared in `file` within `lines`, which are not in `entries`.
+    ///
+    /// `entries` are all the types of the macro invocation at `lines`, so the removed ones were renamed or removed from it.
+    pub fn prune(&mut self, file: &str, lines: RangeInclusive<u32>, entries: &[LockEntry]) {
+        let (pruned, kept) = std::mem::take(&mut self.entries)
+            .into_values()
+            .partition(|locked| {
+                split_location(&locked.location)
+                    .is_some_and(|(locked_file, line)| locked_file == file && lines.contains(&line))
+                    && entries.iter().all(|entry| entry.name != locked.name)
+            });
+        self.pruned = pruned;
+        self.entries = kept
+            .into_iter()
+            .map(|entry: LockEntry| (entry.name.clone(), entry))
+            .collect();
+    }
+
+    /// Check that `entry` agrees with the locked entries: its id can only change with a version bump,
+    /// also when the type is renamed, and no other type has the same id.
+    ///
+    /// A type is renamed if it's declared where a type pruned by [`Lockfile::prune`] was.
+    pub fn check(&self, entry: &LockEntry) -> Result<(), String> {
+        let file = self.path.display();
+        if let Some(renamed) = self.pruned.iter().find(|pruned| {
+            pruned.location == entry.location
+                && pruned.version == entry.version
+                && pruned.id != entry.id
+        }) {
+            return Err(format!(
+                "`{}` is declared where `{}` is locked in `{file}`, renaming it changed the id without a version bump:\n\
+                 - {renamed}\n\
+                 + {entry}\n\
+                 bump the version of `{}`, or remove the entry of `{}` from `{file}` if the change is intended",
+                entry.name, renamed.name, entry.name, renamed.name
+            ));
+        }
+        if let Some(locked) = self.entries.get(&entry.name) {
+            if locked.id != entry.id && locked.version == entry.version {
+                return Err(format!(
+                    "the id of `{}` changed without a version bump, it's locked in `{file}`:\n\
+                     - {locked}\n\
+                     + {entry}\n\
+                     bump the version of `{}`, or remove its entry from `{file}` if the change is intended",
+                    entry.name, entry.name
+                ));
+            }
+        }
+        if let Some(other) = self
+            .entries
+            .values()
+            .find(|other| other.id == entry.id && other.name != entry.name)
+        {
+            return Err(format!(
+                "the id of `{}` collides with `{}` locked in `{file}`:\n\
+                 \x20 {other}\n\
+                 + {entry}\n\
+                 rename or bump the version of `{}` to get a different id",
+                entry.name, other.name, entry.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Insert or replace the entry with the same name.
+    pub fn insert(&mut self, entry: LockEntry) {
+        self.entries.insert(entry.name.clone(), entry);
+    }
+
+    /// Render the lockfile, the output only depends on the entries.
+    pub fn to_toml(&self) -> String {
+        let mut toml = String::from(
+            "# Generated by fixed-type-id, one `[[type]]` per type, sorted by name.\n",
+        );
+        for entry in self.entries.values() {
+            toml.push_str("\n[[type]]\n");
+            toml.push_str(&format!("name = {}\n", quote(&entry.name)));
+            toml.push_str(&format!("version = \"{}\"\n", entry.version));
+            toml.push_str(&format!("id = \"{}\"\n", format_id(entry.id)));
+            toml.push_str(&format!("location = {}\n", quote(&entry.location)));
+        }
+        toml
+    }
+
+    /// Write the lockfile, if its content changed.
+    pub fn save(&self) -> Result<(), String> {
+        let toml = self.to_toml();
+        if self.original.as_ref() == Some(&toml) {
+            return Ok(());
+        }
+        std::fs::write(&self.path, toml)
+            .map_err(|error| format!("failed to write `{}`: {error}", self.path.display()))
+    }
+}
+
+/// Split a location like `src/lib.rs:12` into the file and the line.
+fn split_location(location: &str) -> Option<(&str, u32)> {
+    let (file, line) = location.rsplit_once(':')?;
+    Some((file, line.parse().ok()?))
+}
+
+/// Format the id as hex, with all the digits of [`RawFixedId`].
+fn format_id(id: RawFixedId) -> String {
+    format!("{:#0width$x}", id, width = 2 + 2 * size_of::<RawFixedId>())
+}
+
+fn parse_id(s: &str) -> Option<RawFixedId> {
+    RawFixedId::from_str_radix(s.strip_prefix("0x")?, 16).ok()
+}
+
+/// Quote a string as a TOML basic string.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixed_type_id::parse_version_value;
+    use crate::lockfile::{LockEntry, Lockfile};
+
+    fn entry(name: &str, version: &str, id: u64) -> LockEntry {
+        entry_at(name, version, id, "src/lib.rs:1")
+    }
+
+    fn entry_at(name: &str, version: &str, id: u64, location: &str) -> LockEntry {
+        LockEntry {
+            name: name.to_string(),
+            version: parse_version_value(version).unwrap(),
+            id: id as _,
+            location: location.to_string(),
+        }
+    }
+
+    fn temp_lockfile(name: &str) -> Lockfile {
+        let path = std::env::temp_dir().join(format!(
+            "fixed_type_id_lockfile_{}_{name}.toml",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        Lockfile::load(path.to_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut lockfile = temp_lockfile("round_trip");
+        lockfile.insert(entry("b", "0.1.0", 2));
+        lockfile.insert(entry("a \"quoted\"", "1.0.0", 1));
+        lockfile.insert(entry("c", "2.0.0-beta.3", 3));
+        lockfile.save().unwrap();
+        let loaded = Lockfile::load(lockfile.path.to_str().unwrap()).unwrap();
+        assert_eq!(loaded.entries, lockfile.entries);
+        assert_eq!(loaded.to_toml(), lockfile.to_toml());
+        assert!(
+            loaded.to_toml().find("name = \"a").unwrap()
+                < loaded.to_toml().find("name = \"b").unwrap()
+        );
+        std::fs::remove_file(&lockfile.path).unwrap();
+    }
+
+    #[test]
+    fn check_changes() {
+        let mut lockfile = temp_lockfile("check_changes");
+        lockfile.insert(entry("a", "0.1.0", 1));
+        // the same id at another location
+        assert!(lockfile.check(&entry("a", "0.1.0", 1)).is_ok());
+        // the id changed with a version bump
+        assert!(lockfile.check(&entry("a", "0.2.0", 3)).is_ok());
+        // the id changed without a version bump
+        let error = lockfile.check(&entry("a", "0.1.0", 3)).unwrap_err();
+        assert!(
+            error.contains("the id of `a` changed without a version bump"),
+            "{error}"
+        );
+        assert!(error.contains("- a 0.1.0 0x"), "{error}");
+        // a new collision
+        let error = lockfile.check(&entry("b", "0.1.0", 1)).unwrap_err();
+        assert!(error.contains("the id of `b` collides with `a`"), "{error}");
+        assert!(lockfile.check(&entry("b", "0.1.0", 2)).is_ok());
+    }
+
+    #[test]
+    fn prune_renamed() {
+        let mut lockfile = temp_lockfile("prune_renamed");
+        lockfile.insert(entry_at("a", "0.1.0", 1, "src/lib.rs:2"));
+        lockfile.insert(entry_at("b", "0.1.0", 2, "src/lib.rs:3"));
+        lockfile.insert(entry_at("c", "0.1.0", 3, "src/lib.rs:4"));
+        lockfile.insert(entry_at("d", "0.1.0", 4, "src/other.rs:3"));
+        lockfile.insert(entry_at("e", "0.1.0", 5, "src/lib.rs:10"));
+
+        // `b` is renamed to `x` and `c` is removed, `d` and `e` belong to other invocations
+        let renamed = entry_at("x", "0.1.0", 6, "src/lib.rs:3");
+        let invocation = [entry_at("a", "0.1.0", 1, "src/lib.rs:2"), renamed.clone()];
+        lockfile.prune("src/lib.rs", 1..=5, &invocation);
+        assert_eq!(lockfile.entries.keys().collect::<Vec<_>>(), ["a", "d", "e"]);
+        let error = lockfile.check(&renamed).unwrap_err();
+        assert!(
+            error.contains("`x` is declared where `b` is locked"),
+            "{error}"
+        );
+        assert!(error.contains("- b 0.1.0 0x"), "{error}");
+        // renamed with a version bump
+        assert!(lockfile
+            .check(&entry_at("x", "0.2.0", 7, "src/lib.rs:3"))
+            .is_ok());
+        // a new type declared where no type was
+        assert!(lockfile
+            .check(&entry_at("y", "0.1.0", 8, "src/lib.rs:6"))
+            .is_ok());
+    }
+}
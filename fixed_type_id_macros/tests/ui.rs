@@ -0,0 +1,10 @@
+//! The diagnostics of the macros, run with `TRYBUILD=overwrite` to update the `.stderr` files after an intended change.
+//!
+//! The `.stderr` files are rendered by the release pinned in `rust-toolchain.toml`, so run this with that toolchain,
+//! not with `+nightly` or a newer stable.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
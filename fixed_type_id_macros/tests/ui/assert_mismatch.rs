@@ -0,0 +1,21 @@
+use fixed_type_id::prelude::*;
+use fixed_type_id::{assert_distinct_ids, assert_fixed_id, assert_same_id, assert_type_name};
+
+pub struct A;
+pub struct B;
+
+fixed_type_id! {
+    A
+}
+
+fixed_type_id! {
+    #[equal_to(A)]
+    B
+}
+
+assert_fixed_id!(A, 0x1234);
+assert_type_name!(A, "a::A");
+assert_same_id!(A, u8);
+assert_distinct_ids!(u8, A, B);
+
+fn main() {}
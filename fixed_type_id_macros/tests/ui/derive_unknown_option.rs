@@ -0,0 +1,7 @@
+use fixed_type_id::prelude::*;
+
+#[derive(FixedTypeId)]
+#[fixed_type_id(versoin = "1.0.0")]
+pub struct A;
+
+fn main() {}
@@ -0,0 +1,18 @@
+use fixed_type_id::prelude::*;
+
+pub struct A;
+pub struct B;
+
+fixed_type_id! {
+    #[store_in_file("ids.toml")]
+    #[lock_in_file("ids.lock.toml")]
+    A
+}
+
+fixed_type_id! {
+    #[random_id]
+    #[lock_in_file("ids.lock.toml")]
+    B
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use fixed_type_id::prelude::*;
+
+pub struct Pair<T, U>(T, U);
+
+fixed_type_id! {
+    Pair<T: FixedTypeId, u8>
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use fixed_type_id::prelude::*;
+
+pub struct Pair<T, U> {
+    _data: (T, U),
+}
+
+fixed_type_id! {
+    #[phantom(V = "_")]
+    Pair<T: FixedTypeId, U:>
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use fixed_type_id::prelude::*;
+
+pub struct A;
+
+fixed_type_id! {
+    #[store_in_file(ids)]
+    A
+}
+
+fn main() {}
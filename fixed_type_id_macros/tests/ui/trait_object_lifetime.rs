@@ -0,0 +1,9 @@
+use fixed_type_id::prelude::*;
+
+pub trait Handler {}
+
+fixed_type_id! {
+    dyn Handler + Send + 'a
+}
+
+fn main() {}
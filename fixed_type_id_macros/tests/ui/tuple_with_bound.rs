@@ -0,0 +1,7 @@
+use fixed_type_id::prelude::*;
+
+fixed_type_id! {
+    (T: FixedTypeId, u8)
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use fixed_type_id::prelude::*;
+
+pub struct Pair<T, U> {
+    _data: (T, U),
+}
+
+fixed_type_id! {
+    Pair<T: FixedTypeId, U:>
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use fixed_type_id::prelude::*;
+
+pub struct A;
+
+fixed_type_id! {
+    #[versoin((1, 0, 0))]
+    A
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use fixed_type_id::prelude::*;
+
+pub struct Buf<const N: usize>;
+
+fixed_type_id! {
+    Buf<const N: Length>
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use fixed_type_id::prelude::*;
+
+fixed_type_id! {
+    unsafe extern "C" fn(u8, ...) -> i32
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use fixed_type_id::prelude::*;
+
+pub struct A;
+
+fixed_type_id! {
+    #[version(1)]
+    A
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use fixed_type_id::prelude::*;
+
+pub struct A;
+pub struct B;
+
+fixed_type_id! {
+    #[version("1.x.0")]
+    A
+}
+
+fixed_type_id! {
+    #[version("1.2.0-beta.01")]
+    B
+}
+
+fn main() {}
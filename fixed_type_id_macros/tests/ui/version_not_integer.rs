@@ -0,0 +1,10 @@
+use fixed_type_id::prelude::*;
+
+pub struct A;
+
+fixed_type_id! {
+    #[version((1, "2", 0))]
+    A
+}
+
+fn main() {}